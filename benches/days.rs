@@ -0,0 +1,105 @@
+//Benchmarks parsing and each part of every day on the real inputs.
+//Days without an input file use a generated one of about the same size instead.
+//Run a single day with e.g. `cargo bench -- day17/`
+use aoc2023::*;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+//reset clears anything a day remembers between runs, like memoized results
+fn bench_day<S: Solution>(c: &mut Criterion, reset: Option<fn()>) {
+    let input = match (input::load(S::DAY, None), generate::get(S::DAY)) {
+        (Ok(input), _) => input,
+        (Err(_), Some(generator)) => generator.generate(generator.default_size, 0),
+        (Err(e), None) => {
+            eprintln!("Skipping day {}: {:#}", S::DAY, e);
+            return;
+        }
+    };
+    let parsed = match S::parse(&input) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Skipping day {}: {:#}", S::DAY, e);
+            return;
+        }
+    };
+
+    let mut group = c.benchmark_group(format!("day{}", S::DAY));
+    //some parts take seconds per iteration, so keep the sample count low
+    group.sample_size(10);
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(&input))));
+    match reset {
+        //otherwise every iteration after the first would only look up the remembered answers
+        Some(reset) => {
+            group.bench_function("part1", |b| {
+                b.iter_batched(
+                    reset,
+                    |()| S::part1(black_box(&parsed)),
+                    BatchSize::PerIteration,
+                )
+            });
+            if S::HAS_PART2 {
+                group.bench_function("part2", |b| {
+                    b.iter_batched(
+                        reset,
+                        |()| S::part2(black_box(&parsed)),
+                        BatchSize::PerIteration,
+                    )
+                });
+            }
+        }
+        None => {
+            group.bench_function("part1", |b| b.iter(|| S::part1(black_box(&parsed))));
+            if S::HAS_PART2 {
+                group.bench_function("part2", |b| b.iter(|| S::part2(black_box(&parsed))));
+            }
+        }
+    }
+    group.finish();
+}
+
+macro_rules! reset {
+    () => {
+        None
+    };
+    ($reset:expr) => {
+        Some($reset as fn())
+    };
+}
+
+macro_rules! bench_days {
+    ($($solution:ty $(=> $reset:expr)?),* $(,)?) => {
+        fn bench_all(c: &mut Criterion) {
+            $(bench_day::<$solution>(c, reset!($($reset)?));)*
+        }
+    };
+}
+
+bench_days! {
+    day1::Day1,
+    day2::Day2,
+    day3::Day3,
+    day4::Day4,
+    day5::Day5,
+    day6::Day6,
+    day7::Day7,
+    day8::Day8,
+    day9::Day9,
+    day10::Day10,
+    day11::Day11,
+    day12::Day12 => day12::memoized_flush_count_arrangements,
+    day13::Day13,
+    day14::Day14,
+    day15::Day15,
+    day16::Day16,
+    day17::Day17,
+    day18::Day18,
+    day19::Day19,
+    day20::Day20,
+    day21::Day21,
+    day22::Day22,
+    day23::Day23,
+    day24::Day24,
+    day25::Day25,
+}
+
+criterion_group!(benches, bench_all);
+criterion_main!(benches);
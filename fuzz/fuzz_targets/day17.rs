@@ -0,0 +1,9 @@
+#![no_main]
+
+use aoc2023::{day17::Day17, Solution};
+use libfuzzer_sys::fuzz_target;
+
+//any input has to parse or fail with an error, never panic
+fuzz_target!(|input: &str| {
+    let _ = Day17::parse(input);
+});
@@ -0,0 +1,289 @@
+use crate::registry::Part;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::{self, Display},
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+//environment variable that overrides where the answers file is kept
+pub const ANSWERS_FILE_VAR: &str = "AOC_ANSWERS_FILE";
+pub const DEFAULT_ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Correct,
+    Wrong,
+    Unknown,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Status::Correct => "correct",
+            Status::Wrong => "wrong",
+            Status::Unknown => "unknown",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+//What is known about the answers of each day, stored as a TOML file like
+//
+//  [day1]
+//  part1 = "54990"
+//  part2 = "54473"
+//  part2_wrong = ["54431"]
+//
+//where partN is the correct answer and partN_wrong lists answers known to be wrong.
+//Answers can also be written as integers, like part1 = 54990, but are always saved as strings.
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct Answers {
+    days: BTreeMap<u8, DayAnswers>,
+}
+
+#[derive(Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DayAnswers {
+    #[serde(
+        default,
+        deserialize_with = "optional_answer",
+        skip_serializing_if = "Option::is_none"
+    )]
+    part1: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "answer_list",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    part1_wrong: Vec<String>,
+    #[serde(
+        default,
+        deserialize_with = "optional_answer",
+        skip_serializing_if = "Option::is_none"
+    )]
+    part2: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "answer_list",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    part2_wrong: Vec<String>,
+}
+
+//an answer as written in the file, which is compared as a string either way
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawAnswer {
+    Text(String),
+    Integer(i64),
+}
+
+impl From<RawAnswer> for String {
+    fn from(answer: RawAnswer) -> String {
+        match answer {
+            RawAnswer::Text(answer) => answer,
+            RawAnswer::Integer(answer) => answer.to_string(),
+        }
+    }
+}
+
+fn optional_answer<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(Option::<RawAnswer>::deserialize(deserializer)?.map(String::from))
+}
+
+fn answer_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    Ok(Vec::<RawAnswer>::deserialize(deserializer)?
+        .into_iter()
+        .map(String::from)
+        .collect())
+}
+
+impl DayAnswers {
+    fn part(&self, part: Part) -> (&Option<String>, &Vec<String>) {
+        match part {
+            Part::One => (&self.part1, &self.part1_wrong),
+            Part::Two => (&self.part2, &self.part2_wrong),
+        }
+    }
+
+    fn part_mut(&mut self, part: Part) -> (&mut Option<String>, &mut Vec<String>) {
+        match part {
+            Part::One => (&mut self.part1, &mut self.part1_wrong),
+            Part::Two => (&mut self.part2, &mut self.part2_wrong),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == DayAnswers::default()
+    }
+}
+
+pub fn default_path() -> PathBuf {
+    env::var_os(ANSWERS_FILE_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_ANSWERS_FILE))
+}
+
+impl Answers {
+    //a missing file is the same as an empty one
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(contents) => Answers::parse(&contents)
+                .with_context(|| format!("Invalid answers file '{}'", path.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => {
+                Err(e).with_context(|| format!("Could not read answers file '{}'", path.display()))
+            }
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let days = toml::from_str::<BTreeMap<String, DayAnswers>>(contents)?
+            .into_iter()
+            .map(|(key, answers)| {
+                let day = key
+                    .strip_prefix("day")
+                    .and_then(|day| day.parse().ok())
+                    .with_context(|| format!("Expected a table named dayN, found '{}'", key))?;
+                Ok((day, answers))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Answers { days })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.to_string())
+            .with_context(|| format!("Could not write answers file '{}'", path.display()))
+    }
+
+    pub fn has_day(&self, day: u8) -> bool {
+        self.days.contains_key(&day)
+    }
+
+    pub fn correct(&self, day: u8, part: Part) -> Option<&str> {
+        let answers = self.days.get(&day)?;
+        answers.part(part).0.as_deref()
+    }
+
+    pub fn status(&self, day: u8, part: Part, answer: &str) -> Status {
+        let Some(answers) = self.days.get(&day) else {
+            return Status::Unknown;
+        };
+        match answers.part(part) {
+            (Some(correct), _) if correct == answer => Status::Correct,
+            //there is only one correct answer, so anything else must be wrong
+            (Some(_), _) => Status::Wrong,
+            (None, wrong) if wrong.iter().any(|wrong| wrong == answer) => Status::Wrong,
+            (None, _) => Status::Unknown,
+        }
+    }
+
+    //records the answer with the given status, forgetting anything recorded about it before
+    pub fn mark(&mut self, day: u8, part: Part, answer: &str, status: Status) {
+        let answers = self.days.entry(day).or_default();
+        let (correct, wrong) = answers.part_mut(part);
+
+        if correct.as_deref() == Some(answer) {
+            *correct = None;
+        }
+        wrong.retain(|wrong| wrong != answer);
+
+        match status {
+            Status::Correct => *correct = Some(answer.to_string()),
+            Status::Wrong => wrong.push(answer.to_string()),
+            Status::Unknown => (),
+        }
+
+        if answers.is_empty() {
+            self.days.remove(&day);
+        }
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        //written table by table so the days stay in numeric order
+        for (i, (day, answers)) in self.days.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "[day{}]", day)?;
+            write!(f, "{}", toml::to_string(answers).map_err(|_| fmt::Error)?)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = "\
+[day1]
+part1 = \"142\"
+part2_wrong = [\"280\", \"282\"]
+
+[day10]
+part2 = \"4\"
+";
+
+    #[test]
+    fn status() -> Result<()> {
+        let answers = Answers::parse(ANSWERS)?;
+        assert_eq!(answers.status(1, Part::One, "142"), Status::Correct);
+        assert_eq!(answers.status(1, Part::One, "141"), Status::Wrong);
+        assert_eq!(answers.status(1, Part::Two, "282"), Status::Wrong);
+        assert_eq!(answers.status(1, Part::Two, "281"), Status::Unknown);
+        assert_eq!(answers.status(2, Part::One, "8"), Status::Unknown);
+        Ok(())
+    }
+
+    #[test]
+    fn mark_and_round_trip() -> Result<()> {
+        let mut answers = Answers::parse(ANSWERS)?;
+        answers.mark(1, Part::Two, "281", Status::Correct);
+        answers.mark(1, Part::Two, "282", Status::Unknown);
+        answers.mark(2, Part::One, "8", Status::Wrong);
+        answers.mark(10, Part::Two, "4", Status::Unknown);
+
+        let expected = "\
+[day1]
+part1 = \"142\"
+part2 = \"281\"
+part2_wrong = [\"280\"]
+
+[day2]
+part1_wrong = [\"8\"]
+";
+        assert_eq!(answers.to_string(), expected);
+        assert_eq!(Answers::parse(expected)?, answers);
+        Ok(())
+    }
+
+    #[test]
+    fn integer_answers() -> Result<()> {
+        let answers = Answers::parse("[day1]\npart1 = 142\npart2_wrong = [280, \"282\"]")?;
+        assert_eq!(answers.status(1, Part::One, "142"), Status::Correct);
+        assert_eq!(answers.status(1, Part::Two, "280"), Status::Wrong);
+        assert_eq!(answers.status(1, Part::Two, "282"), Status::Wrong);
+        assert_eq!(
+            answers.to_string(),
+            "[day1]\npart1 = \"142\"\npart2_wrong = [\"280\", \"282\"]\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_bad_tables() {
+        assert!(Answers::parse("[dayone]\npart1 = \"1\"").is_err());
+        assert!(Answers::parse("[day1]\npart3 = \"1\"").is_err());
+    }
+}
@@ -1,72 +1,142 @@
+use crate::{stream::Streaming, Solution};
 use anyhow::{Context, Result};
+use std::{convert::Infallible, io::BufRead};
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day1.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input<'a> = Vec<&'a str>;
+    type Error = Infallible;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Infallible> {
+        Ok(input.lines().collect())
+    }
+
+    fn part1(lines: &Self::Input<'_>) -> Result<u64> {
+        lines
+            .iter()
+            .map(|line| calibration_value(line).map(u64::from))
+            .sum()
+    }
+
+    fn part2(lines: &Self::Input<'_>) -> Result<u64> {
+        lines
+            .iter()
+            .map(|line| calibration_value_with_words(line).map(u64::from))
+            .sum()
+    }
 }
 
-fn part1(input: &str) -> Result<u32> {
-    input
-        .lines()
-        .map(|line| {
-            let first = line.chars().find(char::is_ascii_digit);
-            let last = line.chars().rfind(char::is_ascii_digit);
-            let (first, last) = first
-                .zip(last)
-                .context(format!("No digit in line: {}", line))?;
-
-            let num = format!("{}{}", first, last);
-            let num = num.parse::<u32>()?;
-            Ok(num)
-        })
-        .sum()
+impl Streaming for Day1 {
+    fn stream_part1(input: impl BufRead) -> Result<u64> {
+        input
+            .lines()
+            .map(|line| calibration_value(&line?).map(u64::from))
+            .sum()
+    }
+
+    fn stream_part2(input: impl BufRead) -> Result<u64> {
+        input
+            .lines()
+            .map(|line| calibration_value_with_words(&line?).map(u64::from))
+            .sum()
+    }
 }
 
-fn part2(input: &str) -> Result<u32> {
-    input
-        .lines()
-        .map(|line| {
-            const NUMS: [(&str, &str); 9] = [
-                ("1", "one"),
-                ("2", "two"),
-                ("3", "three"),
-                ("4", "four"),
-                ("5", "five"),
-                ("6", "six"),
-                ("7", "seven"),
-                ("8", "eight"),
-                ("9", "nine"),
-            ];
-
-            let first = NUMS
+//combines the first and last digit in the line
+pub fn calibration_value(line: &str) -> Result<u32> {
+    let first = line.chars().find(char::is_ascii_digit);
+    let last = line.chars().rfind(char::is_ascii_digit);
+    let (first, last) = first
+        .zip(last)
+        .context(format!("No digit in line: {}", line))?;
+
+    let num = format!("{}{}", first, last);
+    let num = num.parse::<u32>()?;
+    Ok(num)
+}
+
+//like calibration_value, but digits may also be spelled out as words
+pub fn calibration_value_with_words(line: &str) -> Result<u32> {
+    const NUMS: [(&str, &str); 9] = [
+        ("1", "one"),
+        ("2", "two"),
+        ("3", "three"),
+        ("4", "four"),
+        ("5", "five"),
+        ("6", "six"),
+        ("7", "seven"),
+        ("8", "eight"),
+        ("9", "nine"),
+    ];
+
+    let first = NUMS
+        .into_iter()
+        .flat_map(|(num, word)| {
+            line.find(num)
                 .into_iter()
-                .flat_map(|(num, word)| {
-                    line.find(num)
-                        .into_iter()
-                        .chain(line.find(word))
-                        .map(move |index| (index, num))
-                })
-                .min_by_key(|(index, _)| *index)
-                .map(|(_, num)| num)
-                .context("Error finding minimum index")?;
-
-            let last = NUMS
+                .chain(line.find(word))
+                .map(move |index| (index, num))
+        })
+        .min_by_key(|(index, _)| *index)
+        .map(|(_, num)| num)
+        .context("Error finding minimum index")?;
+
+    let last = NUMS
+        .into_iter()
+        .flat_map(|(num, word)| {
+            line.rfind(num)
                 .into_iter()
-                .flat_map(|(num, word)| {
-                    line.rfind(num)
-                        .into_iter()
-                        .chain(line.rfind(word))
-                        .map(move |index| (index, num))
-                })
-                .max_by_key(|(index, _)| *index)
-                .map(|(_, num)| num)
-                .context("Error finding maximum index")?;
-
-            let num = format!("{}{}", first, last);
-            let num = num.parse::<u32>()?;
-            Ok(num)
+                .chain(line.rfind(word))
+                .map(move |index| (index, num))
         })
-        .sum()
+        .max_by_key(|(index, _)| *index)
+        .map(|(_, num)| num)
+        .context("Error finding maximum index")?;
+
+    let num = format!("{}{}", first, last);
+    let num = num.parse::<u32>()?;
+    Ok(num)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE1: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+    const EXAMPLE2: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day1::solve_part1(EXAMPLE1)?, 142);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day1::solve_part2(EXAMPLE2)?, 281);
+        Ok(())
+    }
+
+    #[test]
+    fn overlapping_words() -> Result<()> {
+        assert_eq!(calibration_value_with_words("eightwo")?, 82);
+        Ok(())
+    }
 }
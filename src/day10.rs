@@ -1,23 +1,57 @@
-use anyhow::{bail, Context, Result};
-use itertools::Itertools;
-use std::{collections::HashSet, iter::successors};
-
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day10.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+use crate::{
+    error::{self, Location},
+    geometry::Direction,
+    grid::{self, Grid, Position},
+    registry::Part,
+    render::{Color, Image, Pixel, Render},
+    Solution,
+};
+use anyhow::Result;
+use std::collections::HashSet;
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input<'a> = Grid<Pipe>;
+    type Error = Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        parse_grid(input)
+    }
+
+    fn part1(grid: &Self::Input<'_>) -> Result<usize> {
+        let main_path = main_path(grid)?;
+        let farthest = main_path.len() / 2;
+        Ok(farthest)
+    }
+
+    fn part2(grid: &Self::Input<'_>) -> Result<usize> {
+        inside_area(grid)
+    }
 }
 
-enum Direction {
-    North,
-    South,
-    East,
-    West,
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error(transparent)]
+    Grid(grid::ErrorKind),
+    #[error("No start in input")]
+    NoStart,
+    #[error("No pipes connected to start")]
+    NoConnectedPipes,
+    #[error("Pipe leads out of the grid")]
+    LeftGrid,
+    #[error("Pipe is not connected to the previous one")]
+    DeadEnd,
 }
 
+pub type Error = error::Error<ErrorKind>;
+
 #[derive(PartialEq)]
-enum Pipe {
+pub enum Pipe {
     Southeast,
     Northeast,
     Southwest,
@@ -30,10 +64,7 @@ enum Pipe {
 
 impl Pipe {
     fn north_facing(&self) -> bool {
-        matches!(
-            self,
-            Self::Northeast | Self::Northwest | Self::Northsouth | Self::Start
-        )
+        matches!(self, Self::Northeast | Self::Northwest | Self::Northsouth)
     }
 
     fn connected_to_south(&self) -> bool {
@@ -53,64 +84,45 @@ impl Pipe {
     }
 }
 
-impl From<char> for Pipe {
-    fn from(value: char) -> Self {
-        match value {
-            'F' => Pipe::Southeast,
-            'L' => Pipe::Northeast,
-            '-' => Pipe::Eastwest,
-            '7' => Pipe::Southwest,
-            'J' => Pipe::Northwest,
-            '|' => Pipe::Northsouth,
-            'S' => Pipe::Start,
-            _ => Pipe::None,
-        }
-    }
+fn find_start(grid: &Grid<Pipe>) -> Result<Position, Error> {
+    grid.iter()
+        .find(|(_, pipe)| **pipe == Pipe::Start)
+        .map(|(position, _)| position)
+        .ok_or_else(|| Error::new(Location::new(1, 1), ErrorKind::NoStart))
 }
 
-fn main_path(grid: &Vec<Vec<Pipe>>) -> Result<impl Iterator<Item = (usize, usize)> + '_> {
-    let row_count = grid.len();
-    let col_count = grid[0].len();
-    let start = (0..row_count)
-        .cartesian_product(0..col_count)
-        .find(|&(i, j)| grid[i][j] == Pipe::Start)
-        .context("No start in input")?;
+//every position of the loop, starting with the start
+pub fn main_path(grid: &Grid<Pipe>) -> Result<Vec<Position>, Error> {
+    let start = find_start(grid)?;
 
     let start_direction = {
-        let (i, j) = start;
-
-        //assume start is not on an edge
-        let northern = &grid[i - 1][j];
-        let southern = &grid[i + 1][j];
-        let eastern = &grid[i][j + 1];
-        let western = &grid[i][j - 1];
+        let neighbor = |direction| {
+            grid.neighbor(start, direction)
+                .map(|position| &grid[position])
+        };
 
-        if northern.connected_to_south() {
+        if neighbor(Direction::North).is_some_and(Pipe::connected_to_south) {
             Direction::North
-        } else if southern.connected_to_north() {
+        } else if neighbor(Direction::South).is_some_and(Pipe::connected_to_north) {
             Direction::South
-        } else if eastern.connected_to_west() {
+        } else if neighbor(Direction::East).is_some_and(Pipe::connected_to_west) {
             Direction::East
-        } else if western.connected_to_east() {
+        } else if neighbor(Direction::West).is_some_and(Pipe::connected_to_east) {
             Direction::West
         } else {
-            bail!("No pipes connected to start");
+            return Err(Error::new(start, ErrorKind::NoConnectedPipes));
         }
     };
 
-    let path = successors(Some((start, start_direction)), |(position, direction)| {
-        let &(row, col) = position;
-        let next_position = match direction {
-            Direction::North => (row - 1, col),
-            Direction::East => (row, col + 1),
-            Direction::South => (row + 1, col),
-            Direction::West => (row, col - 1),
-        };
-
-        let (next_row, next_col) = next_position;
-        let next_pipe = &grid[next_row][next_col];
-        let next_direction = match (direction, next_pipe) {
-            (_, Pipe::Start) => return None,
+    let mut path = vec![start];
+    let (mut position, mut direction) = (start, start_direction);
+    loop {
+        let next_position = grid
+            .neighbor(position, direction)
+            .ok_or_else(|| Error::new(position, ErrorKind::LeftGrid))?;
+        let next_pipe = &grid[next_position];
+        direction = match (direction, next_pipe) {
+            (_, Pipe::Start) => return Ok(path),
 
             (Direction::North, Pipe::Southeast) => Direction::East,
             (Direction::North, Pipe::Southwest) => Direction::West,
@@ -128,52 +140,159 @@ fn main_path(grid: &Vec<Vec<Pipe>>) -> Result<impl Iterator<Item = (usize, usize
             (Direction::West, Pipe::Southeast) => Direction::South,
             (Direction::West, Pipe::Eastwest) => Direction::West,
 
-            _ => panic!("Dead end"),
+            _ => return Err(Error::new(next_position, ErrorKind::DeadEnd)),
         };
+        position = next_position;
+        path.push(position);
+    }
+}
 
-        Some((next_position, next_direction))
+pub fn parse_grid(input: &str) -> Result<Grid<Pipe>, Error> {
+    Grid::parse(input, |c| match c {
+        'F' => Some(Pipe::Southeast),
+        'L' => Some(Pipe::Northeast),
+        '-' => Some(Pipe::Eastwest),
+        '7' => Some(Pipe::Southwest),
+        'J' => Some(Pipe::Northwest),
+        '|' => Some(Pipe::Northsouth),
+        'S' => Some(Pipe::Start),
+        '.' => Some(Pipe::None),
+        _ => None,
     })
-    .map(|(position, _)| position);
-    Ok(path)
+    .map_err(|e| e.map(ErrorKind::Grid))
 }
 
-fn parse_grid(input: &str) -> Vec<Vec<Pipe>> {
-    input
-        .lines()
-        .map(|line| line.chars().map(Into::into).collect())
-        .collect()
+pub fn inside_area(grid: &Grid<Pipe>) -> Result<usize> {
+    Ok(inside_positions(grid)?.len())
 }
 
-fn inside_area(grid: Vec<Vec<Pipe>>) -> Result<usize> {
-    let main_path = main_path(&grid)?.collect::<HashSet<_>>();
-    let area = grid
-        .into_iter()
+//every position enclosed by the main loop
+pub fn inside_positions(grid: &Grid<Pipe>) -> Result<Vec<Position>> {
+    let main_path = main_path(grid)?.into_iter().collect::<HashSet<_>>();
+
+    //the start's hidden pipe is north facing only if the loop continues north from it
+    let start = find_start(grid)?;
+    let start_north_facing = grid
+        .neighbor(start, Direction::North)
+        .is_some_and(|north| main_path.contains(&north) && grid[north].connected_to_south());
+
+    let positions = grid
+        .rows()
         .enumerate()
-        .map(|(row_index, row)| {
+        .flat_map(|(row_index, row)| {
             row.iter()
                 .enumerate()
                 .scan(false, |inside, (col_index, pipe)| {
                     let is_main_path = main_path.contains(&(row_index, col_index));
-                    if (pipe.north_facing() || *pipe == Pipe::Start) && is_main_path {
+                    let north_facing = match pipe {
+                        Pipe::Start => start_north_facing,
+                        pipe => pipe.north_facing(),
+                    };
+                    if north_facing && is_main_path {
                         *inside ^= true;
                     }
-                    Some(*inside && !is_main_path)
+                    Some((*inside && !is_main_path).then_some((row_index, col_index)))
                 })
-                .filter(|is_inside| *is_inside)
-                .count()
+                .flatten()
+                .collect::<Vec<_>>()
         })
-        .sum();
-    Ok(area)
+        .collect();
+    Ok(positions)
 }
 
-fn part1(input: &str) -> Result<usize> {
-    let grid = parse_grid(input);
-    let main_path = main_path(&grid)?;
-    let farthest = main_path.count() / 2;
-    Ok(farthest)
+impl Render for Day10 {
+    //the main loop, and for part 2 the tiles it encloses
+    fn render(grid: &Grid<Pipe>, part: Part) -> Result<Image> {
+        let mut image = Image::new(grid, |pipe| {
+            let symbol = match pipe {
+                Pipe::Southeast => '┌',
+                Pipe::Northeast => '└',
+                Pipe::Southwest => '┐',
+                Pipe::Northwest => '┘',
+                Pipe::Eastwest => '─',
+                Pipe::Northsouth => '│',
+                Pipe::Start => 'S',
+                Pipe::None => '.',
+            };
+            Pixel::new(symbol, Color::DARK_GRAY)
+        });
+        image.overlay(main_path(grid)?, Color::YELLOW);
+        if part == Part::Two {
+            image.overlay(inside_positions(grid)?, Color::GREEN);
+        }
+        Ok(image)
+    }
 }
 
-fn part2(input: &str) -> Result<usize> {
-    let grid = parse_grid(input);
-    inside_area(grid)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIMPLE_LOOP: &str = "\
+.....
+.S-7.
+.|.|.
+.L-J.
+.....";
+
+    const COMPLEX_LOOP: &str = "\
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...";
+
+    const ENCLOSED: &str = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+
+    const SQUEEZED: &str = "\
+..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........";
+
+    #[test]
+    fn part1_examples() -> Result<()> {
+        assert_eq!(Day10::solve_part1(SIMPLE_LOOP)?, 4);
+        assert_eq!(Day10::solve_part1(COMPLEX_LOOP)?, 8);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_examples() -> Result<()> {
+        assert_eq!(Day10::solve_part2(ENCLOSED)?, 4);
+        assert_eq!(Day10::solve_part2(SQUEEZED)?, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn dead_end() -> Result<()> {
+        let grid = Day10::parse(".....\n.S-7.\n.|.|.\n.L-..\n.....")?;
+        let error = main_path(&grid).err();
+        assert_eq!(error, Some(Error::new((3, 3), ErrorKind::DeadEnd)));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_pipe() {
+        let error = Day10::parse(".....\n.S-7.\n.|x|.\n.L-J.").err();
+        let expected = Error::new(
+            (2, 2),
+            ErrorKind::Grid(grid::ErrorKind::UnexpectedCharacter('x')),
+        );
+        assert_eq!(error, Some(expected));
+    }
 }
@@ -1,31 +1,48 @@
-use anyhow::{anyhow, Result};
+use crate::{
+    grid::{self, Grid, Position},
+    Solution,
+};
+use anyhow::Result;
 use itertools::Itertools;
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day11.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input<'a> = Grid<Option<Galaxy>>;
+    type Error = grid::Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, grid::Error> {
+        parse_image(input)
+    }
+
+    fn part1(image: &Self::Input<'_>) -> Result<usize> {
+        let galaxies = locate_galaxies(image, 1);
+        let sum = sum_distances(&galaxies);
+        Ok(sum)
+    }
+
+    fn part2(image: &Self::Input<'_>) -> Result<usize> {
+        let galaxies = locate_galaxies(image, 999999);
+        let sum = sum_distances(&galaxies);
+        Ok(sum)
+    }
 }
 
-struct Galaxy;
-
-fn parse_image(input: &str) -> Result<Vec<Vec<Option<Galaxy>>>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| match c {
-                    '#' => Ok(Some(Galaxy)),
-                    '.' => Ok(None),
-                    _ => Err(anyhow!("Unexpected character: '{}'", c)),
-                })
-                .collect()
-        })
-        .collect()
+pub struct Galaxy;
+
+pub fn parse_image(input: &str) -> Result<Grid<Option<Galaxy>>, grid::Error> {
+    Grid::parse(input, |c| match c {
+        '#' => Some(Some(Galaxy)),
+        '.' => Some(None),
+        _ => None,
+    })
 }
 
-fn manhattan_distance((x1, y1): (usize, usize), (x2, y2): (usize, usize)) -> usize {
+pub fn manhattan_distance((x1, y1): Position, (x2, y2): Position) -> usize {
     y1.abs_diff(y2) + x1.abs_diff(x2)
 }
 
@@ -39,25 +56,19 @@ fn expand(init: usize, expansion: usize, expanded_lines: impl Iterator<Item = us
     })
 }
 
-fn locate_galaxies(image: &Vec<Vec<Option<Galaxy>>>, expansion: usize) -> Vec<(usize, usize)> {
-    let row_count = image.len();
-    let col_count = image[0].len();
+pub fn locate_galaxies(image: &Grid<Option<Galaxy>>, expansion: usize) -> Vec<Position> {
+    let empty_rows = (0..image.row_count())
+        .rev()
+        .filter(|&i| image.row(i).iter().all(|point| point.is_none()));
 
-    let empty_rows = (0..row_count)
+    let empty_cols = (0..image.col_count())
         .rev()
-        .filter(|&i| image[i].iter().all(|point| point.is_none()));
+        .filter(|&i| image.column(i).all(|point| point.is_none()));
 
-    let empty_cols = (0..col_count).rev().filter(|&i| {
-        image
-            .iter()
-            .map(move |row| &row[i])
-            .all(|point| point.is_none())
-    });
-
-    (0..row_count)
-        .cartesian_product(0..col_count)
-        .filter(|&(row, col)| image[row][col].is_some())
-        .map(|(row, col)| {
+    image
+        .iter()
+        .filter(|(_, point)| point.is_some())
+        .map(|((row, col), _)| {
             let row = expand(row, expansion, empty_rows.clone());
             let col = expand(col, expansion, empty_cols.clone());
             (row, col)
@@ -65,7 +76,7 @@ fn locate_galaxies(image: &Vec<Vec<Option<Galaxy>>>, expansion: usize) -> Vec<(u
         .collect()
 }
 
-fn sum_distances(galaxies: &[(usize, usize)]) -> usize {
+pub fn sum_distances(galaxies: &[Position]) -> usize {
     galaxies
         .iter()
         .tuple_combinations()
@@ -73,16 +84,92 @@ fn sum_distances(galaxies: &[(usize, usize)]) -> usize {
         .sum()
 }
 
-fn part1(input: &str) -> Result<usize> {
-    let image = parse_image(input)?;
-    let galaxies = locate_galaxies(&image, 1);
-    let sum = sum_distances(&galaxies);
-    Ok(sum)
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day11::solve_part1(EXAMPLE)?, 374);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        let image = Day11::parse(EXAMPLE)?;
+        assert_eq!(sum_distances(&locate_galaxies(&image, 9)), 1030);
+        assert_eq!(sum_distances(&locate_galaxies(&image, 99)), 8410);
+        assert_eq!(Day11::part2(&image)?, 82000210);
+        Ok(())
+    }
+
+    //copies every empty row and column into the picture and measures the distances there
+    fn expand_literally(image: &[Vec<bool>], expansion: usize) -> usize {
+        let rows = image
+            .iter()
+            .flat_map(|row| {
+                let copies = if row.contains(&true) {
+                    1
+                } else {
+                    expansion + 1
+                };
+                std::iter::repeat_n(row.clone(), copies)
+            })
+            .collect_vec();
+        let empty_cols = (0..image[0].len())
+            .map(|col| image.iter().all(|row| !row[col]))
+            .collect_vec();
+        let galaxies = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row_index, row)| {
+                let mut col_index = 0;
+                row.iter()
+                    .zip(&empty_cols)
+                    .filter_map(move |(&galaxy, &empty)| {
+                        let position = (row_index, col_index);
+                        col_index += if empty { expansion + 1 } else { 1 };
+                        galaxy.then_some(position)
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        galaxies
+            .iter()
+            .tuple_combinations()
+            .map(|(&(row1, col1), &(row2, col2))| row1.abs_diff(row2) + col1.abs_diff(col2))
+            .sum()
+    }
 
-fn part2(input: &str) -> Result<usize> {
-    let image = parse_image(input)?;
-    let galaxies = locate_galaxies(&image, 999999);
-    let sum = sum_distances(&galaxies);
-    Ok(sum)
+    fn image() -> impl Strategy<Value = Vec<Vec<bool>>> {
+        (1..8_usize, 1..8_usize).prop_flat_map(|(rows, cols)| {
+            prop::collection::vec(prop::collection::vec(prop::bool::weighted(0.2), cols), rows)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn matches_literal_expansion(image in image(), expansion in 1..4_usize) {
+            let input = image
+                .iter()
+                .map(|row| row.iter().map(|&galaxy| if galaxy { '#' } else { '.' }).collect::<String>())
+                .join("\n");
+            let galaxies = locate_galaxies(&parse_image(&input)?, expansion);
+            prop_assert_eq!(sum_distances(&galaxies), expand_literally(&image, expansion));
+        }
+    }
 }
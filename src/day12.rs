@@ -1,49 +1,121 @@
-use anyhow::{bail, Context, Result};
+use crate::{
+    error::{self, Location},
+    stream::{self, Streaming},
+    Solution,
+};
+use anyhow::Result;
 use itertools::Itertools;
 use memoize::memoize;
-use std::iter::repeat;
-use tap::Pipe;
-
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day12.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+use std::{io::BufRead, iter::repeat_n};
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input<'a> = Vec<(Vec<Spring>, Vec<usize>)>;
+    type Error = Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_line(line).map_err(|e| e.offset_by(Location::new(i + 1, 1))))
+            .collect()
+    }
+
+    fn part1(records: &Self::Input<'_>) -> Result<usize> {
+        let sum = records
+            .iter()
+            .cloned()
+            .map(|(springs, counts)| count_arrangements(springs, counts))
+            .sum();
+        Ok(sum)
+    }
+
+    fn part2(records: &Self::Input<'_>) -> Result<usize> {
+        let sum = records
+            .iter()
+            .cloned()
+            .map(|(springs, counts)| unfold(springs, counts))
+            .map(|(springs, counts)| count_arrangements(springs, counts))
+            .sum();
+        Ok(sum)
+    }
 }
 
+impl Streaming for Day12 {
+    fn stream_part1(input: impl BufRead) -> Result<usize> {
+        stream::parse_lines(input, parse_line)
+            .map_ok(|(springs, counts)| count_record(springs, counts))
+            .sum()
+    }
+
+    fn stream_part2(input: impl BufRead) -> Result<usize> {
+        stream::parse_lines(input, parse_line)
+            .map_ok(|(springs, counts)| unfold(springs, counts))
+            .map_ok(|(springs, counts)| count_record(springs, counts))
+            .sum()
+    }
+}
+
+//Like count_arrangements, but forgets the memoized counts afterwards.
+//Records rarely share them, and keeping them all would grow without bound on a long stream.
+fn count_record(springs: Vec<Spring>, counts: Vec<usize>) -> usize {
+    let count = count_arrangements(springs, counts);
+    memoized_flush_count_arrangements();
+    count
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected the counts after a space")]
+    MissingCounts,
+    #[error("Unexpected spring '{0}'")]
+    InvalidSpring(char),
+    #[error("Invalid count '{0}'")]
+    InvalidCount(String),
+}
+
+pub type Error = error::Error<ErrorKind>;
+
 #[derive(PartialEq, Clone, Eq, Hash)]
-enum Spring {
+pub enum Spring {
     Operational,
     Damaged,
     Unknown,
 }
 
-fn parse_line(line: &str) -> Result<(Vec<Spring>, Vec<usize>)> {
+//locations in errors are relative to the line
+pub fn parse_line(line: &str) -> Result<(Vec<Spring>, Vec<usize>), Error> {
     let (springs, counts) = line
         .split_once(' ')
-        .context(format!("Error parsing line: {}", line))?;
+        .ok_or_else(|| Error::new(Location::end_of(line), ErrorKind::MissingCounts))?;
     let springs = springs
-        .chars()
-        .map(|c| {
-            match c {
-                '#' => Spring::Damaged,
-                '.' => Spring::Operational,
-                '?' => Spring::Unknown,
-                _ => bail!("Unexpected character: '{}'", c),
-            }
-            .pipe(Ok)
+        .char_indices()
+        .map(|(i, c)| match c {
+            '#' => Ok(Spring::Damaged),
+            '.' => Ok(Spring::Operational),
+            '?' => Ok(Spring::Unknown),
+            _ => Err(Error::at(line, &springs[i..], ErrorKind::InvalidSpring(c))),
         })
-        .collect::<Result<_>>()?;
+        .collect::<Result<_, _>>()?;
 
     let counts = counts
         .split(',')
-        .map(str::parse)
+        .map(|count| {
+            count
+                .parse()
+                .map_err(|_| Error::at(line, count, ErrorKind::InvalidCount(count.to_string())))
+        })
         .collect::<Result<_, _>>()?;
     Ok((springs, counts))
 }
 
 #[memoize]
-fn count_arrangements(springs: Vec<Spring>, counts: Vec<usize>) -> usize {
+pub fn count_arrangements(springs: Vec<Spring>, counts: Vec<usize>) -> usize {
     if counts.is_empty() {
         if springs.contains(&Spring::Damaged) {
             return 0;
@@ -94,29 +166,92 @@ fn count_assuming_damaged(springs: &[Spring], counts: &[usize]) -> usize {
 }
 
 #[allow(unstable_name_collisions)]
-fn unfold(springs: Vec<Spring>, counts: Vec<usize>) -> (Vec<Spring>, Vec<usize>) {
-    let springs = repeat(springs)
-        .take(5)
+pub fn unfold(springs: Vec<Spring>, counts: Vec<usize>) -> (Vec<Spring>, Vec<usize>) {
+    let springs = repeat_n(springs, 5)
         .intersperse(vec![Spring::Unknown])
         .flatten()
         .collect::<Vec<_>>();
-    let counts = repeat(counts).take(5).flatten().collect::<Vec<_>>();
+    let counts = repeat_n(counts, 5).flatten().collect::<Vec<_>>();
     (springs, counts)
 }
 
-fn part1(input: &str) -> Result<usize> {
-    input
-        .lines()
-        .map(parse_line)
-        .map_ok(|(springs, counts)| count_arrangements(springs, counts))
-        .sum()
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day12::solve_part1(EXAMPLE)?, 21);
+        Ok(())
+    }
 
-fn part2(input: &str) -> Result<usize> {
-    input
-        .lines()
-        .map(parse_line)
-        .map_ok(|(springs, counts)| unfold(springs, counts))
-        .map_ok(|(springs, counts)| count_arrangements(springs, counts))
-        .sum()
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day12::solve_part2(EXAMPLE)?, 525152);
+        Ok(())
+    }
+
+    #[test]
+    fn arrangements_per_line() -> Result<()> {
+        let counts = Day12::parse(EXAMPLE)?
+            .into_iter()
+            .map(|(springs, counts)| count_arrangements(springs, counts))
+            .collect::<Vec<_>>();
+        assert_eq!(counts, [1, 4, 1, 1, 4, 10]);
+        Ok(())
+    }
+
+    //tries every way of replacing the unknown springs
+    fn enumerate_arrangements(springs: &str, counts: &[usize]) -> usize {
+        let unknowns = springs.matches('?').count();
+        (0..1_usize << unknowns)
+            .filter(|assignment| {
+                let mut bit = 0;
+                let springs = springs
+                    .chars()
+                    .map(|c| match c {
+                        '?' => {
+                            bit += 1;
+                            if assignment >> (bit - 1) & 1 == 1 {
+                                '#'
+                            } else {
+                                '.'
+                            }
+                        }
+                        c => c,
+                    })
+                    .collect::<String>();
+                let groups = springs
+                    .split('.')
+                    .filter(|group| !group.is_empty())
+                    .map(str::len)
+                    .collect_vec();
+                groups == counts
+            })
+            .count()
+    }
+
+    proptest! {
+        #[test]
+        fn matches_enumeration(
+            springs in "[.#?]{1,12}",
+            counts in prop::collection::vec(1..4_usize, 1..4),
+        ) {
+            let line = format!("{} {}", springs, counts.iter().join(","));
+            let (parsed_springs, parsed_counts) = parse_line(&line)?;
+            prop_assert_eq!(
+                count_arrangements(parsed_springs, parsed_counts),
+                enumerate_arrangements(&springs, &counts)
+            );
+        }
+    }
 }
@@ -1,20 +1,39 @@
+use crate::{
+    error::Location,
+    grid::{self, Grid},
+    Solution,
+};
 use anyhow::{bail, Result};
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day13.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
-}
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
 
-fn pattern_to_string(pattern: &[Vec<char>]) -> String {
-    pattern
-        .iter()
-        .map(|row| row.iter().collect::<String>() + "\n")
-        .collect()
+    type Input<'a> = Vec<Grid<char>>;
+    type Error = grid::Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, grid::Error> {
+        input
+            .split("\n\n")
+            .map(|pattern| {
+                parse_pattern(pattern).map_err(|e| e.offset_by(Location::of(input, pattern)))
+            })
+            .collect()
+    }
+
+    fn part1(patterns: &Self::Input<'_>) -> Result<usize> {
+        patterns.iter().map(summarize).sum()
+    }
+
+    fn part2(patterns: &Self::Input<'_>) -> Result<usize> {
+        patterns.iter().map(summarize_with_smudge).sum()
+    }
 }
 
-fn summarize(pattern: &Vec<Vec<char>>) -> Result<usize> {
+pub fn summarize(pattern: &Grid<char>) -> Result<usize> {
     if let Some(columns) = check_vertical_symmetry(pattern) {
         return Ok(columns);
     }
@@ -22,50 +41,30 @@ fn summarize(pattern: &Vec<Vec<char>>) -> Result<usize> {
         return Ok(100 * rows);
     }
 
-    bail!(
-        "No symmetry in this pattern:\n{}",
-        pattern_to_string(pattern)
-    );
+    bail!("No symmetry in this pattern:\n{}", pattern);
 }
 
-fn check_vertical_symmetry(pattern: &[Vec<char>]) -> Option<usize> {
-    let col_count = pattern[0].len();
-
-    for i in 1..col_count {
-        let is_mirrored = pattern.iter().all(|row| {
+pub fn check_vertical_symmetry(pattern: &Grid<char>) -> Option<usize> {
+    (1..pattern.col_count()).find(|&i| {
+        pattern.rows().all(|row| {
             let left_half = row[..i].iter().rev();
             let right_half = row[i..].iter();
 
             left_half.zip(right_half).all(|(left, right)| left == right)
-        });
-
-        if is_mirrored {
-            return Some(i);
-        }
-    }
-    None
+        })
+    })
 }
 
-fn check_horizontal_symmetry(pattern: &Vec<Vec<char>>) -> Option<usize> {
-    let row_count = pattern.len();
-
-    for i in 1..row_count {
-        let top_half = pattern[..i].iter().rev();
-        let bottom_half = pattern[i..].iter();
-        let is_mirrored = top_half.zip(bottom_half).all(|(top, bottom)| top == bottom);
-
-        if is_mirrored {
-            return Some(i);
-        }
-    }
-    None
+//a horizontal line of symmetry is a vertical one in the transposed pattern
+pub fn check_horizontal_symmetry(pattern: &Grid<char>) -> Option<usize> {
+    check_vertical_symmetry(&pattern.transpose())
 }
 
-fn parse_pattern(pattern: &str) -> Vec<Vec<char>> {
-    pattern.lines().map(|line| line.chars().collect()).collect()
+pub fn parse_pattern(pattern: &str) -> Result<Grid<char>, grid::Error> {
+    Grid::parse(pattern, Some)
 }
 
-fn summarize_with_smudge(pattern: &[Vec<char>]) -> Result<usize> {
+pub fn summarize_with_smudge(pattern: &Grid<char>) -> Result<usize> {
     if let Some(vertical_line) = check_vertical_symmetry_with_smudge(pattern) {
         return Ok(vertical_line);
     }
@@ -74,69 +73,62 @@ fn summarize_with_smudge(pattern: &[Vec<char>]) -> Result<usize> {
         return Ok(horizontal_line * 100);
     }
 
-    bail!(
-        "No symmetry in this pattern:\n{}",
-        pattern_to_string(pattern)
-    );
-}
-
-fn check_vertical_symmetry_with_smudge(pattern: &[Vec<char>]) -> Option<usize> {
-    let col_count = pattern[0].len();
-
-    for i in 1..col_count {
-        let length = i.min(col_count - i);
-        let left_half = pattern
-            .iter()
-            .flat_map(|row| row[..i].iter().rev().take(length));
-        let right_half = pattern.iter().flat_map(|row| row[i..].iter().take(length));
-
-        let differences = left_half
-            .zip(right_half)
-            .filter(|(left, right)| left != right)
-            .count();
-
-        if differences == 1 {
-            return Some(i);
-        }
-    }
-    None
+    bail!("No symmetry in this pattern:\n{}", pattern);
 }
 
-fn check_horizontal_symmetry_with_smudge(pattern: &[Vec<char>]) -> Option<usize> {
-    let row_count = pattern.len();
-
-    for i in 1..row_count {
-        let top_half = pattern[..i].iter().rev();
-        let bottom_half = pattern[i..].iter();
-        let differences = top_half
-            .zip(bottom_half)
-            .map(|(top, bottom)| {
-                top.iter()
-                    .zip(bottom.iter())
-                    .filter(|(top, bottom)| top != bottom)
+pub fn check_vertical_symmetry_with_smudge(pattern: &Grid<char>) -> Option<usize> {
+    (1..pattern.col_count()).find(|&i| {
+        let differences = pattern
+            .rows()
+            .map(|row| {
+                let left_half = row[..i].iter().rev();
+                let right_half = row[i..].iter();
+
+                left_half
+                    .zip(right_half)
+                    .filter(|(left, right)| left != right)
                     .count()
             })
             .sum::<usize>();
 
-        if differences == 1 {
-            return Some(i);
-        }
-    }
-    None
+        differences == 1
+    })
 }
 
-fn part1(input: &str) -> Result<usize> {
-    input
-        .split("\n\n")
-        .map(parse_pattern)
-        .map(|pattern| summarize(&pattern))
-        .sum()
+pub fn check_horizontal_symmetry_with_smudge(pattern: &Grid<char>) -> Option<usize> {
+    check_vertical_symmetry_with_smudge(&pattern.transpose())
 }
 
-fn part2(input: &str) -> Result<usize> {
-    input
-        .split("\n\n")
-        .map(parse_pattern)
-        .map(|pattern| summarize_with_smudge(&pattern))
-        .sum()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.##..##.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day13::solve_part1(EXAMPLE)?, 405);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day13::solve_part2(EXAMPLE)?, 400);
+        Ok(())
+    }
 }
@@ -1,105 +1,88 @@
-use anyhow::{bail, Result};
+use crate::{
+    geometry::Direction,
+    grid::{self, Grid},
+    registry::Part,
+    render::{Color, Image, Pixel, Render},
+    Solution,
+};
+use anyhow::Result;
 use itertools::Itertools;
 use std::{collections::HashMap, iter::successors};
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day14.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
-}
+pub struct Day14;
 
-fn part1(input: &str) -> Result<usize> {
-    let mut rocks = parse_input(input)?;
-    tilt(&mut rocks, Direction::North);
-    let load = calculate_load(&rocks);
-    Ok(load)
-}
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input<'a> = RockPlatform;
+    type Error = grid::Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, grid::Error> {
+        parse_input(input)
+    }
+
+    fn part1(rocks: &Self::Input<'_>) -> Result<usize> {
+        let mut rocks = rocks.clone();
+        tilt(&mut rocks, Direction::North);
+        let load = calculate_load(&rocks);
+        Ok(load)
+    }
 
-fn part2(input: &str) -> Result<usize> {
-    let mut rocks = parse_input(input)?;
+    fn part2(rocks: &Self::Input<'_>) -> Result<usize> {
+        let mut rocks = rocks.clone();
 
-    cycle_rocks(&mut rocks);
+        cycle_rocks(&mut rocks);
 
-    let load = calculate_load(&rocks);
+        let load = calculate_load(&rocks);
 
-    Ok(load)
+        Ok(load)
+    }
 }
 
-type RockPlatform = Vec<Vec<Option<Rock>>>;
+pub type RockPlatform = Grid<Option<Rock>>;
 
 #[derive(PartialEq, Eq, Clone, Copy, Hash)]
-enum Rock {
+pub enum Rock {
     Round,
     Cube,
 }
 
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
-fn parse_input(input: &str) -> Result<RockPlatform> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| {
-                    Ok(match c {
-                        'O' => Some(Rock::Round),
-                        '#' => Some(Rock::Cube),
-                        '.' => None,
-                        _ => bail!("Unexpected char: '{}'", c),
-                    })
-                })
-                .collect()
-        })
-        .collect()
+pub fn parse_input(input: &str) -> Result<RockPlatform, grid::Error> {
+    Grid::parse(input, |c| match c {
+        'O' => Some(Some(Rock::Round)),
+        '#' => Some(Some(Rock::Cube)),
+        '.' => Some(None),
+        _ => None,
+    })
 }
 
-fn tilt(rocks: &mut RockPlatform, direction: Direction) {
-    let direction = match direction {
-        Direction::North => (-1, 0),
-        Direction::East => (0, 1),
-        Direction::South => (1, 0),
-        Direction::West => (0, -1),
-    };
-
-    let row_count = rocks.len();
-    let column_count = rocks[0].len();
+pub fn tilt(rocks: &mut RockPlatform, direction: Direction) {
+    let backwards = direction.reverse();
 
-    for (i, j) in (0..row_count).cartesian_product(0..column_count) {
-        if rocks[i][j] != Some(Rock::Round) {
+    for position in rocks.positions() {
+        if rocks[position] != Some(Rock::Round) {
             continue;
         }
 
-        rocks[i][j] = None;
+        rocks[position] = None;
 
-        let cube_loc = successors(Some((i, j)), |(i, j)| {
-            let i = i.checked_add_signed(direction.0)?;
-            let j = j.checked_add_signed(direction.1)?;
-            rocks.get(i)?.get(j).map(|_| (i, j))
+        let cube_loc = successors(Some(position), |&position| {
+            rocks.neighbor(position, direction)
         })
-        .find_or_last(|&(i, j)| rocks[i][j] == Some(Rock::Cube));
+        .find_or_last(|&position| rocks[position] == Some(Rock::Cube));
 
-        let (i, j) = successors(cube_loc, |(i, j)| {
-            let i = i.checked_add_signed(-direction.0)?;
-            let j = j.checked_add_signed(-direction.1)?;
-            rocks.get(i)?.get(j).map(|_| (i, j))
-        })
-        .find_or_last(|&(i, j)| rocks[i][j].is_none())
-        .expect("Iterator should never be empty");
-        rocks[i][j] = Some(Rock::Round);
+        let position = successors(cube_loc, |&position| rocks.neighbor(position, backwards))
+            .find_or_last(|&position| rocks[position].is_none())
+            .expect("Iterator should never be empty");
+        rocks[position] = Some(Rock::Round);
     }
 }
 
-fn calculate_load(rocks: &RockPlatform) -> usize {
-    let column_count = rocks[0].len();
-    let columns = (0..column_count).map(|i| rocks.iter().map(move |row| &row[i]));
-
-    columns
+pub fn calculate_load(rocks: &RockPlatform) -> usize {
+    rocks
+        .columns()
         .map(|column| {
             column
                 .rev()
@@ -113,7 +96,7 @@ fn calculate_load(rocks: &RockPlatform) -> usize {
 }
 
 //execute the spin cycle 1000000000 times
-fn cycle_rocks(rocks: &mut Vec<Vec<Option<Rock>>>) {
+pub fn cycle_rocks(rocks: &mut RockPlatform) {
     let (start, end) = find_cycle(rocks);
     let cycle_length = end - start;
     let leftover = (1_000_000_000 - start) % cycle_length;
@@ -123,7 +106,7 @@ fn cycle_rocks(rocks: &mut Vec<Vec<Option<Rock>>>) {
     }
 }
 
-fn find_cycle(rocks: &mut Vec<Vec<Option<Rock>>>) -> (usize, usize) {
+fn find_cycle(rocks: &mut RockPlatform) -> (usize, usize) {
     let mut i = 0;
     let mut map = HashMap::new();
 
@@ -138,9 +121,76 @@ fn find_cycle(rocks: &mut Vec<Vec<Option<Rock>>>) -> (usize, usize) {
     }
 }
 
-fn spin_cycle(rocks: &mut RockPlatform) {
+pub fn spin_cycle(rocks: &mut RockPlatform) {
     tilt(rocks, Direction::North);
     tilt(rocks, Direction::West);
     tilt(rocks, Direction::South);
     tilt(rocks, Direction::East);
 }
+
+impl Render for Day14 {
+    //the rocks after tilting north for part 1, or after all the spin cycles for part 2
+    fn render(rocks: &RockPlatform, part: Part) -> Result<Image> {
+        let mut rocks = rocks.clone();
+        match part {
+            Part::One => tilt(&mut rocks, Direction::North),
+            Part::Two => cycle_rocks(&mut rocks),
+        }
+        let image = Image::new(&rocks, |rock| match rock {
+            Some(Rock::Round) => Pixel::new('O', Color::YELLOW),
+            Some(Rock::Cube) => Pixel::new('#', Color::GRAY),
+            None => Pixel::new('.', Color::DARK_GRAY),
+        });
+        Ok(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day14::solve_part1(EXAMPLE)?, 136);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day14::solve_part2(EXAMPLE)?, 64);
+        Ok(())
+    }
+
+    #[test]
+    fn tilt_north() -> Result<()> {
+        let mut rocks = Day14::parse(EXAMPLE)?;
+        tilt(&mut rocks, Direction::North);
+        let expected = Day14::parse(
+            "\
+OOOO.#.O..
+OO..#....#
+OO..O##..O
+O..#.OO...
+........#.
+..#....#.#
+..O..#.O.O
+..O.......
+#....###..
+#....#....",
+        )?;
+        assert!(rocks == expected);
+        Ok(())
+    }
+}
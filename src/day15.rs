@@ -1,34 +1,41 @@
+use crate::Solution;
 use anyhow::{bail, Result};
 use core::array;
+use std::convert::Infallible;
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day15.txt");
-    println!("Part 1 = {}", part1(input));
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
-}
+pub struct Day15;
 
-fn part1(input: &str) -> usize {
-    input
-        .lines()
-        .flat_map(|line| line.split(','))
-        .map(hash)
-        .sum()
-}
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Input<'a> = Vec<&'a str>;
+    type Error = Infallible;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Infallible> {
+        Ok(input.lines().flat_map(|line| line.split(',')).collect())
+    }
+
+    fn part1(steps: &Self::Input<'_>) -> Result<usize> {
+        Ok(steps.iter().map(|step| hash(step)).sum())
+    }
 
-fn part2(input: &str) -> Result<usize> {
-    let steps = input.lines().flat_map(|line| line.split(','));
-    let boxes = create_boxes(steps)?;
-    let focusing_power = focusing_power(&boxes);
-    Ok(focusing_power)
+    fn part2(steps: &Self::Input<'_>) -> Result<usize> {
+        let boxes = create_boxes(steps.iter().copied())?;
+        let focusing_power = focusing_power(&boxes);
+        Ok(focusing_power)
+    }
 }
 
-fn hash(step: &str) -> usize {
+pub fn hash(step: &str) -> usize {
     step.chars()
         .fold(0, |current, c| ((current + (c as usize)) * 17) % 256)
 }
 
-fn create_boxes<'a>(steps: impl Iterator<Item = &'a str>) -> Result<[Vec<(String, usize)>; 256]> {
+pub fn create_boxes<'a>(
+    steps: impl Iterator<Item = &'a str>,
+) -> Result<[Vec<(String, usize)>; 256]> {
     let mut boxes = array::from_fn(|_| vec![]);
     for step in steps {
         let tokens = step.split(['-', '=']).collect::<Vec<_>>();
@@ -53,7 +60,7 @@ fn create_boxes<'a>(steps: impl Iterator<Item = &'a str>) -> Result<[Vec<(String
     Ok(boxes)
 }
 
-fn focusing_power(boxes: &[Vec<(String, usize)>]) -> usize {
+pub fn focusing_power(boxes: &[Vec<(String, usize)>]) -> usize {
     boxes
         .iter()
         .enumerate()
@@ -65,3 +72,23 @@ fn focusing_power(boxes: &[Vec<(String, usize)>]) -> usize {
         })
         .sum()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(hash("HASH"), 52);
+        assert_eq!(Day15::solve_part1(EXAMPLE)?, 1320);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day15::solve_part2(EXAMPLE)?, 145);
+        Ok(())
+    }
+}
@@ -1,23 +1,53 @@
-use anyhow::{bail, Context, Result};
-use itertools::{chain, Itertools};
+use crate::{
+    error::{self, Location},
+    geometry::Direction,
+    grid::{self, Grid, Position},
+    registry::Part,
+    render::{Color, Image, Pixel, Render},
+    Solution,
+};
+use anyhow::{Context, Result};
+use itertools::chain;
 use std::collections::HashSet;
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day16.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+
+    type Input<'a> = Grid<Tile>;
+    type Error = Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        parse_input(input)
+    }
+
+    fn part1(contraption: &Self::Input<'_>) -> Result<usize> {
+        let energized_tiles = count_energized_tiles((0, 0), Direction::East, contraption);
+        Ok(energized_tiles)
+    }
+
+    fn part2(contraption: &Self::Input<'_>) -> Result<usize> {
+        get_border(contraption)
+            .map(|(position, direction)| count_energized_tiles(position, direction, contraption))
+            .max()
+            .context("Error finding maximum")
+    }
 }
 
-#[derive(Hash, PartialEq, Eq, Clone, Copy)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error(transparent)]
+    Grid(grid::ErrorKind),
+    #[error("Expected at least one row and column")]
+    Empty,
 }
 
-enum Tile {
+pub type Error = error::Error<ErrorKind>;
+
+pub enum Tile {
     ForwardMirror,
     BackwardMirror,
     VerticalSplitter,
@@ -26,24 +56,28 @@ enum Tile {
 }
 
 impl Tile {
-    fn reflect(&self, direction: Direction) -> impl Iterator<Item = Direction> {
+    pub fn reflect(&self, direction: Direction) -> impl Iterator<Item = Direction> {
         match (self, direction) {
-            (Tile::BackwardMirror, Direction::Right) => vec![Direction::Down],
-            (Tile::BackwardMirror, Direction::Up) => vec![Direction::Left],
-            (Tile::BackwardMirror, Direction::Down) => vec![Direction::Right],
-            (Tile::BackwardMirror, Direction::Left) => vec![Direction::Up],
-
-            (Tile::ForwardMirror, Direction::Down) => vec![Direction::Left],
-            (Tile::ForwardMirror, Direction::Up) => vec![Direction::Right],
-            (Tile::ForwardMirror, Direction::Left) => vec![Direction::Down],
-            (Tile::ForwardMirror, Direction::Right) => vec![Direction::Up],
-
-            (Tile::VerticalSplitter, Direction::Left | Direction::Right) => {
-                vec![Direction::Up, Direction::Down]
+            //a \ mirror turns east and west beams right, north and south beams left
+            (Tile::BackwardMirror, Direction::East | Direction::West) => {
+                vec![direction.turn_right()]
+            }
+            (Tile::BackwardMirror, Direction::North | Direction::South) => {
+                vec![direction.turn_left()]
+            }
+
+            //a / mirror does the opposite
+            (Tile::ForwardMirror, Direction::East | Direction::West) => vec![direction.turn_left()],
+            (Tile::ForwardMirror, Direction::North | Direction::South) => {
+                vec![direction.turn_right()]
             }
 
-            (Tile::HorizontalSplitter, Direction::Up | Direction::Down) => {
-                vec![Direction::Left, Direction::Right]
+            (Tile::VerticalSplitter, Direction::East | Direction::West) => {
+                vec![Direction::North, Direction::South]
+            }
+
+            (Tile::HorizontalSplitter, Direction::North | Direction::South) => {
+                vec![Direction::West, Direction::East]
             }
 
             (_, dir) => vec![dir],
@@ -52,48 +86,31 @@ impl Tile {
     }
 }
 
-fn part1(input: &str) -> Result<usize> {
-    let contraption = parse_input(input)?;
-    let energized_tiles = count_energized_tiles((0, 0), Direction::Right, &contraption);
-    Ok(energized_tiles)
-}
+pub fn get_border(contraption: &Grid<Tile>) -> impl Iterator<Item = (Position, Direction)> {
+    let row_count = contraption.row_count();
+    let col_count = contraption.col_count();
 
-fn part2(input: &str) -> Result<usize> {
-    let contraption = parse_input(input)?;
-    get_border(&contraption)
-        .map(|(position, direction)| count_energized_tiles(position, direction, &contraption))
-        .max()
-        .context("Error finding maximum")
-}
-
-fn get_border(contraption: &Vec<Vec<Tile>>) -> impl Iterator<Item = ((usize, usize), Direction)> {
-    let row_count = contraption.len();
-    let col_count = contraption[0].len();
-
-    let left_starts = (0..row_count).map(move |i| ((i, 0), Direction::Right));
-    let right_starts = (0..row_count).map(move |i| ((i, col_count - 1), Direction::Left));
-    let top_starts = (0..col_count).map(move |i| ((0, i), Direction::Down));
-    let bot_starts = (0..col_count).map(move |i| ((row_count - 1, i), Direction::Up));
+    let left_starts = (0..row_count).map(move |i| ((i, 0), Direction::East));
+    let right_starts = (0..row_count).map(move |i| ((i, col_count - 1), Direction::West));
+    let top_starts = (0..col_count).map(move |i| ((0, i), Direction::South));
+    let bot_starts = (0..col_count).map(move |i| ((row_count - 1, i), Direction::North));
 
     chain!(left_starts, right_starts, top_starts, bot_starts)
 }
 
-fn count_energized_tiles(
-    start_position: (usize, usize),
+pub fn count_energized_tiles(
+    start_position: Position,
     start_direction: Direction,
-    contraption: &[Vec<Tile>],
+    contraption: &Grid<Tile>,
 ) -> usize {
-    let next_position = |(row, col): (usize, usize), direction: Direction| {
-        let (row, col) = match direction {
-            Direction::Up => (row.checked_sub(1)?, col),
-            Direction::Down => (row + 1, col),
-            Direction::Left => (row, col.checked_sub(1)?),
-            Direction::Right => (row, col + 1),
-        };
-        contraption.get(row)?.get(col)?;
-        Some((row, col))
-    };
+    energized_tiles(start_position, start_direction, contraption).len()
+}
 
+pub fn energized_tiles(
+    start_position: Position,
+    start_direction: Direction,
+    contraption: &Grid<Tile>,
+) -> HashSet<Position> {
     let mut visited = HashSet::new();
     let mut stack = vec![(start_position, start_direction)];
 
@@ -105,35 +122,84 @@ fn count_energized_tiles(
         visited.insert(pair);
 
         let (position, direction) = pair;
-        let (row, col) = position;
-        let neighbors = contraption[row][col]
+        let neighbors = contraption[position]
             .reflect(direction)
-            .filter_map(|dir| next_position(position, dir).map(|pos| (pos, dir)));
+            .filter_map(|dir| contraption.neighbor(position, dir).map(|pos| (pos, dir)));
         stack.extend(neighbors);
     }
 
-    visited
-        .into_iter()
-        .unique_by(|&(position, _)| position)
-        .count()
+    visited.into_iter().map(|(position, _)| position).collect()
 }
 
-fn parse_input(input: &str) -> Result<Vec<Vec<Tile>>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| {
-                    Ok(match c {
-                        '/' => Tile::ForwardMirror,
-                        '\\' => Tile::BackwardMirror,
-                        '|' => Tile::VerticalSplitter,
-                        '-' => Tile::HorizontalSplitter,
-                        '.' => Tile::Empty,
-                        _ => bail!("Unexpected character: {}", c),
-                    })
+impl Render for Day16 {
+    //the tiles energized from the top left for part 1, or from the best border tile for part 2
+    fn render(contraption: &Grid<Tile>, part: Part) -> Result<Image> {
+        let (position, direction) = match part {
+            Part::One => ((0, 0), Direction::East),
+            Part::Two => get_border(contraption)
+                .max_by_key(|&(position, direction)| {
+                    count_energized_tiles(position, direction, contraption)
                 })
-                .collect()
-        })
-        .collect()
+                .context("Error finding maximum")?,
+        };
+        let mut image = Image::new(contraption, |tile| {
+            let symbol = match tile {
+                Tile::ForwardMirror => '/',
+                Tile::BackwardMirror => '\\',
+                Tile::VerticalSplitter => '|',
+                Tile::HorizontalSplitter => '-',
+                Tile::Empty => '.',
+            };
+            Pixel::new(symbol, Color::DARK_GRAY)
+        });
+        image.overlay(
+            energized_tiles(position, direction, contraption),
+            Color::YELLOW,
+        );
+        Ok(image)
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Grid<Tile>, Error> {
+    let grid = Grid::parse(input, |c| match c {
+        '/' => Some(Tile::ForwardMirror),
+        '\\' => Some(Tile::BackwardMirror),
+        '|' => Some(Tile::VerticalSplitter),
+        '-' => Some(Tile::HorizontalSplitter),
+        '.' => Some(Tile::Empty),
+        _ => None,
+    })
+    .map_err(|e| e.map(ErrorKind::Grid))?;
+    if grid.row_count() == 0 || grid.col_count() == 0 {
+        return Err(Error::new(Location::new(1, 1), ErrorKind::Empty));
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day16::solve_part1(EXAMPLE)?, 46);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day16::solve_part2(EXAMPLE)?, 51);
+        Ok(())
+    }
 }
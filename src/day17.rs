@@ -1,125 +1,87 @@
-use anyhow::{anyhow, Context, Result};
+use crate::{
+    error::{self, Location},
+    geometry::{Direction, Point},
+    grid::{self, Grid, Position},
+    registry::Part,
+    render::{Color, Image, Pixel, Render},
+    Solution,
+};
+use anyhow::{anyhow, Result};
 use std::{
     cmp::Reverse,
     collections::{BinaryHeap, HashMap},
+    iter::successors,
 };
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day17.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
-}
-
-fn part1(input: &str) -> Result<usize> {
-    let city = parse_input(input)?;
-    minimum_heat_loss(3, 0, &city)
-}
-
-fn part2(input: &str) -> Result<usize> {
-    let city = parse_input(input)?;
-    minimum_heat_loss(10, 4, &city)
-}
+pub struct Day17;
 
-fn parse_input(input: &str) -> Result<Vec<Vec<usize>>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| {
-                    c.to_digit(10)
-                        .map(|num| num as usize)
-                        .context(anyhow!("Error parsing '{}' into number", c))
-                })
-                .collect::<Result<Vec<_>, _>>()
-        })
-        .collect::<Result<Vec<_>, _>>()
-}
+impl Solution for Day17 {
+    const DAY: u8 = 17;
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
+    type Input<'a> = Grid<usize>;
+    type Error = Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-impl Direction {
-    fn iter() -> impl Iterator<Item = Self> {
-        [Self::North, Self::East, Self::South, Self::West].into_iter()
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        parse_input(input)
     }
 
-    fn opposite(&self) -> Self {
-        match self {
-            Self::East => Self::West,
-            Self::North => Self::South,
-            Self::South => Self::North,
-            Self::West => Self::East,
-        }
+    fn part1(city: &Self::Input<'_>) -> Result<usize> {
+        minimum_heat_loss(3, 0, city)
     }
-}
 
-fn get_neighbor(
-    position: (usize, usize),
-    direction: Direction,
-    city: &[Vec<usize>],
-) -> Option<(usize, usize)> {
-    let (row, col) = position;
-    let (row, col) = match direction {
-        Direction::North => (row.checked_sub(1)?, col),
-        Direction::South => (row + 1, col),
-        Direction::West => (row, col.checked_sub(1)?),
-        Direction::East => (row, col + 1),
-    };
-    city.get(row)?.get(col)?;
-    Some((row, col))
+    fn part2(city: &Self::Input<'_>) -> Result<usize> {
+        minimum_heat_loss(10, 4, city)
+    }
 }
 
-fn get_neighbors(
-    position: (usize, usize),
-    city: &[Vec<usize>],
-) -> impl Iterator<Item = (usize, usize)> + '_ {
-    Direction::iter().filter_map(move |dir| get_neighbor(position, dir, city))
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error(transparent)]
+    Grid(grid::ErrorKind),
+    #[error("Expected at least one row and column")]
+    Empty,
 }
 
-fn manhattan_distance((x1, y1): (usize, usize), (x2, y2): (usize, usize)) -> usize {
-    x1.abs_diff(x2) + y1.abs_diff(y2)
-}
+pub type Error = error::Error<ErrorKind>;
 
-//the direction you would travel moving from src to dest
-//panics if src and dest are not adjacent
-fn get_direction(src: (usize, usize), dest: (usize, usize)) -> Direction {
-    let difference = (
-        dest.0 as isize - src.0 as isize,
-        dest.1 as isize - src.1 as isize,
-    );
-    match difference {
-        (-1, 0) => Direction::North,
-        (0, 1) => Direction::East,
-        (1, 0) => Direction::South,
-        (0, -1) => Direction::West,
-        _ => panic!("Error: {:?} and {:?} must be adjacent", src, dest),
+pub fn parse_input(input: &str) -> Result<Grid<usize>, Error> {
+    let grid = Grid::parse(input, |c| c.to_digit(10).map(|num| num as usize))
+        .map_err(|e| e.map(ErrorKind::Grid))?;
+    if grid.row_count() == 0 || grid.col_count() == 0 {
+        return Err(Error::new(Location::new(1, 1), ErrorKind::Empty));
     }
+    Ok(grid)
 }
 
 #[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Clone)]
 struct Node {
-    position: (usize, usize),
+    position: Position,
     direction: Direction,
     count: usize, //number of moves in the current direction
 }
 
-//Pathfinds from top-left to bottom-right, adding up heats along the way
-//Uses A* Algorithm https://en.wikipedia.org/wiki/A*_search_algorithm
-fn minimum_heat_loss(
+pub fn minimum_heat_loss(
     max_colinear_moves: usize,
     min_colinear_moves: usize,
-    city: &[Vec<usize>],
+    city: &Grid<usize>,
 ) -> Result<usize> {
+    best_route(max_colinear_moves, min_colinear_moves, city).map(|(heat_loss, _)| heat_loss)
+}
+
+//Pathfinds from top-left to bottom-right, adding up heats along the way.
+//Returns the heat loss along with every position of the route.
+//Uses A* Algorithm https://en.wikipedia.org/wiki/A*_search_algorithm
+pub fn best_route(
+    max_colinear_moves: usize,
+    min_colinear_moves: usize,
+    city: &Grid<usize>,
+) -> Result<(usize, Vec<Position>)> {
     let start = (0, 0);
-    let goal = (city.len() - 1, city[0].len() - 1);
+    let goal = (city.row_count() - 1, city.col_count() - 1);
 
-    let heuristic = |node: (usize, usize)| manhattan_distance(node, goal);
+    let heuristic = |node: Position| Point::from(node).manhattan_distance(goal.into());
 
     // The set of discovered nodes that may need to be (re-)expanded.
     // Initially, only the start node is known.
@@ -128,6 +90,9 @@ fn minimum_heat_loss(
     // For node n, g[n] is the cost of the cheapest path from start to n currently known.
     let mut costs = HashMap::new();
 
+    // For node n, came_from[n] is the node immediately preceding it on the cheapest path currently known.
+    let mut came_from = HashMap::new();
+
     // f(n) = g(n) + h(n)
     // f(n) represents our current best guess as to how cheap a path could be from start to finish if it goes through n.
     // g(n) = 0 for the start node
@@ -158,13 +123,16 @@ fn minimum_heat_loss(
     while let Some((_, current)) = open_set.pop() {
         if current.position == goal && current.count >= min_colinear_moves {
             //We found the goal!
-            if let Some((_, &min_heat)) = costs.iter().find(|(node, _)| node.position == goal) {
-                return Ok(min_heat);
-            }
+            let route = successors(Some(&current), |node| came_from.get(*node))
+                .map(|node| node.position)
+                .collect::<Vec<_>>();
+            return Ok((costs[&current], route.into_iter().rev().collect()));
         }
 
-        for neighbor in get_neighbors(current.position, city) {
-            let direction = get_direction(current.position, neighbor);
+        for direction in Direction::iter() {
+            let Some(neighbor) = city.neighbor(current.position, direction) else {
+                continue;
+            };
 
             //skip if too many steps in the same direction
             if current.direction == direction && current.count >= max_colinear_moves {
@@ -177,7 +145,7 @@ fn minimum_heat_loss(
             }
 
             //skip if turning around
-            if current.direction == direction.opposite() {
+            if current.direction == direction.reverse() {
                 continue;
             }
 
@@ -193,7 +161,7 @@ fn minimum_heat_loss(
                 count,
             };
 
-            let heat = city[neighbor.0][neighbor.1];
+            let heat = city[neighbor];
             //the distance from start to the neighbor through current
             let g = costs[&current] + heat;
 
@@ -204,6 +172,7 @@ fn minimum_heat_loss(
 
             //This path to neighbor is better than any previous one. Record it!
             costs.insert(neighbor_node.clone(), g);
+            came_from.insert(neighbor_node.clone(), current.clone());
 
             let h = heuristic(neighbor);
             let f = g + h;
@@ -218,3 +187,62 @@ fn minimum_heat_loss(
     // Open set is empty but goal was never reached
     Err(anyhow!("Could not find path to goal"))
 }
+
+impl Render for Day17 {
+    //the best route for the part's crucible, over the heat of each block
+    fn render(city: &Grid<usize>, part: Part) -> Result<Image> {
+        let (_, route) = match part {
+            Part::One => best_route(3, 0, city)?,
+            Part::Two => best_route(10, 4, city)?,
+        };
+        let mut image = Image::new(city, |&heat| {
+            let symbol = char::from_digit(heat as u32, 10).unwrap_or('?');
+            Pixel::new(
+                symbol,
+                Color::blend(Color::BLACK, Color::GRAY, heat as f64 / 9.0),
+            )
+        });
+        image.overlay(route, Color::RED);
+        Ok(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+    const UNFORTUNATE: &str = "\
+111111111111
+999999999991
+999999999991
+999999999991
+999999999991";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day17::solve_part1(EXAMPLE)?, 102);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_examples() -> Result<()> {
+        assert_eq!(Day17::solve_part2(EXAMPLE)?, 94);
+        assert_eq!(Day17::solve_part2(UNFORTUNATE)?, 71);
+        Ok(())
+    }
+}
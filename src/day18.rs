@@ -1,28 +1,58 @@
-use anyhow::{bail, Result};
+use crate::{
+    error,
+    geometry::{Direction, Point},
+    grid::Grid,
+    registry::Part,
+    render::{Color, Image, Pixel, Render},
+    Solution,
+};
+use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day18.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
-}
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+
+    type Input<'a> = Vec<(Direction, usize, &'a str)>;
+    type Error = Error;
+    type Answer1 = u128;
+    type Answer2 = u128;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        parse_input(input).collect()
+    }
 
-fn part1(input: &str) -> Result<usize> {
-    let dig_plan = parse_input(input).map_ok(|(dir, dist, _)| (dir, dist));
-    let points = dig_plan.process_results(|iter| points(iter))?;
-    let area = area(&points);
-    Ok(area)
+    fn part1(dig_plan: &Self::Input<'_>) -> Result<u128> {
+        let dig_plan = dig_plan.iter().map(|&(dir, dist, _)| (dir, dist));
+        let points = points(dig_plan);
+        let area = area(&points);
+        Ok(area)
+    }
+
+    fn part2(dig_plan: &Self::Input<'_>) -> Result<u128> {
+        let dig_plan = dig_plan.iter().map(|(_, _, color)| parse_color(color));
+        let points = dig_plan.process_results(|iter| points(iter))?;
+        let area = area(&points);
+        Ok(area)
+    }
 }
 
-fn part2(input: &str) -> Result<usize> {
-    let dig_plan = parse_input(input).map(|step| step.and_then(|(_, _, color)| parse_color(color)));
-    let points = dig_plan.process_results(|iter| points(iter))?;
-    let area = area(&points);
-    Ok(area)
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected a direction, distance and color, found '{0}'")]
+    InvalidLine(String),
+    #[error("Unexpected direction '{0}'")]
+    InvalidDirection(String),
+    #[error("Invalid distance '{0}'")]
+    InvalidDistance(String),
+    #[error("Expected a color like '(#70c710)', found '{0}'")]
+    InvalidColor(String),
 }
 
-fn parse_color(color: &str) -> Result<(Direction, usize)> {
+pub type Error = error::Error<ErrorKind>;
+
+pub fn parse_color(color: &str) -> Result<(Direction, usize)> {
     let color = color
         .chars()
         .filter(char::is_ascii_hexdigit)
@@ -30,69 +60,199 @@ fn parse_color(color: &str) -> Result<(Direction, usize)> {
     let color = usize::from_str_radix(&color, 16)?;
     let distance = color / 16; //all but last digit
     let direction = color % 16; //last digit
-    let direction = match direction {
-        0 => Direction::Right,
-        1 => Direction::Down,
-        2 => Direction::Left,
-        3 => Direction::Up,
-        _ => bail!("Cannot parse '{}' into direction", direction),
-    };
+    let direction = Direction::from_digit(direction as u32)
+        .context(format!("Cannot parse '{}' into direction", direction))?;
     Ok((direction, distance))
 }
 
-enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
+//colors are checked here so part 2 can rely on them
+pub fn parse_input(input: &str) -> impl Iterator<Item = Result<(Direction, usize, &str), Error>> {
+    input.lines().map(move |line| {
+        let error = |token: &str, kind| Error::at(input, token, kind);
 
-fn parse_input(input: &str) -> impl Iterator<Item = Result<(Direction, usize, &str)>> {
-    input.lines().map(|line| {
-        let Some((direction, distance, color)) = line.split_whitespace().collect_tuple() else {
-            bail!("Error parsing line: {}", line)
-        };
-        let direction = match direction {
-            "L" => Direction::Left,
-            "R" => Direction::Right,
-            "U" => Direction::Up,
-            "D" => Direction::Down,
-            _ => bail!("Unexpected direction: '{}'", direction),
-        };
-        let distance = distance.parse::<usize>()?;
-        Ok((direction, distance, color))
+        let (direction, distance, color) = line
+            .split_whitespace()
+            .collect_tuple()
+            .ok_or_else(|| error(line, ErrorKind::InvalidLine(line.to_string())))?;
+        let direction = direction
+            .chars()
+            .exactly_one()
+            .ok()
+            .and_then(Direction::from_letter)
+            .ok_or_else(|| {
+                error(
+                    direction,
+                    ErrorKind::InvalidDirection(direction.to_string()),
+                )
+            })?;
+        //small enough that points never overflow
+        let distance = distance
+            .parse::<u32>()
+            .map_err(|_| error(distance, ErrorKind::InvalidDistance(distance.to_string())))?;
+
+        let is_valid_color = color
+            .strip_prefix("(#")
+            .and_then(|color| color.strip_suffix(')'))
+            .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+            && parse_color(color).is_ok();
+        if !is_valid_color {
+            return Err(error(color, ErrorKind::InvalidColor(color.to_string())));
+        }
+        Ok((direction, distance as usize, color))
     })
 }
 
-fn points(steps: impl Iterator<Item = (Direction, usize)>) -> Vec<(isize, isize)> {
+pub fn points(steps: impl Iterator<Item = (Direction, usize)>) -> Vec<Point> {
     steps
-        .scan((0, 0), |point, (direction, distance)| {
-            let distance = distance as isize;
-            match direction {
-                Direction::Left => point.0 -= distance,
-                Direction::Right => point.0 += distance,
-                Direction::Up => point.1 += distance,
-                Direction::Down => point.1 -= distance,
-            };
-
+        .scan(Point::default(), |point, (direction, distance)| {
+            *point += direction.unit() * distance as isize;
             Some(*point)
         })
         .collect()
 }
 
-fn area(polygon: &[(isize, isize)]) -> usize {
+//done in 128 bits since the products of coordinates can easily overflow 64
+pub fn area(polygon: &[Point]) -> u128 {
     let inner_area = polygon
         .iter()
         .circular_tuple_windows()
-        .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
-        .sum::<isize>()
+        .map(|(p1, p2)| p1.x as i128 * p2.y as i128 - p2.x as i128 * p1.y as i128)
+        .sum::<i128>()
         .unsigned_abs();
 
     let edges = polygon
         .iter()
         .circular_tuple_windows()
-        .map(|((x1, y1), (x2, y2))| x1.abs_diff(*x2) + y1.abs_diff(*y2))
-        .sum::<usize>();
+        .map(|(&p1, &p2)| p1.manhattan_distance(p2) as u128)
+        .sum::<u128>();
 
     (inner_area + edges) / 2 + 1
 }
+
+//drawing more cells than this takes too long to be worth it
+const MAX_RENDERED_CELLS: usize = 4_000_000;
+
+//the color a dig plan color stands for, e.g. (#70c710)
+fn paint(color: &str) -> Color {
+    let hex = color.trim_start_matches("(#").trim_end_matches(')');
+    u32::from_str_radix(hex, 16)
+        .map(|rgb| Color((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+        .unwrap_or(Color::RED)
+}
+
+impl Render for Day18 {
+    //the trench painted in the plan's colors for part 1. The part 2 trench is usually too big to draw.
+    fn render(dig_plan: &Self::Input<'_>, part: Part) -> Result<Image> {
+        let steps = match part {
+            Part::One => dig_plan
+                .iter()
+                .map(|&(direction, distance, color)| (direction, distance, paint(color)))
+                .collect_vec(),
+            Part::Two => dig_plan
+                .iter()
+                .map(|(_, _, color)| parse_color(color).map(|(dir, dist)| (dir, dist, Color::RED)))
+                .collect::<Result<_>>()?,
+        };
+
+        let corners = points(
+            steps
+                .iter()
+                .map(|&(direction, distance, _)| (direction, distance)),
+        );
+        let (min_x, max_x) = corners
+            .iter()
+            .map(|point| point.x)
+            .chain([0])
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+        let (min_y, max_y) = corners
+            .iter()
+            .map(|point| point.y)
+            .chain([0])
+            .minmax()
+            .into_option()
+            .unwrap_or_default();
+        let rows = max_y.abs_diff(min_y) + 1;
+        let cols = max_x.abs_diff(min_x) + 1;
+        let length = steps
+            .iter()
+            .map(|&(_, distance, _)| distance)
+            .sum::<usize>();
+        if rows.saturating_mul(cols) > MAX_RENDERED_CELLS || length > MAX_RENDERED_CELLS {
+            bail!("The {}x{} trench is too big to render", rows, cols);
+        }
+        let position = |point: Point| ((point.y - min_y) as usize, (point.x - min_x) as usize);
+
+        let mut pixels = Grid::filled(rows, cols, Pixel::new('.', Color::DARK_GRAY));
+
+        //the lagoon is every cell between pairs of vertical edges in its row
+        for y in min_y..=max_y {
+            let crossings = corners
+                .iter()
+                .circular_tuple_windows()
+                .filter(|(p1, p2)| p1.x == p2.x && p1.y.min(p2.y) <= y && y < p1.y.max(p2.y))
+                .map(|(p1, _)| p1.x)
+                .sorted_unstable()
+                .collect_vec();
+            for (&start, &end) in crossings.iter().tuples() {
+                for x in start..=end {
+                    pixels[position(Point::new(x, y))] = Pixel::new('#', Color::GRAY);
+                }
+            }
+        }
+
+        let mut point = Point::default();
+        for &(direction, distance, color) in &steps {
+            for _ in 0..distance {
+                point = point.step(direction);
+                pixels[position(point)] = Pixel::new('#', color);
+            }
+        }
+
+        Ok(Image { pixels })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day18::solve_part1(EXAMPLE)?, 62);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day18::solve_part2(EXAMPLE)?, 952408144115);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_color() {
+        let error = Day18::parse("R 6 (#70c710)\nD 5 (#0dc574)").err();
+        let expected = Error::new(
+            error::Location::new(2, 5),
+            ErrorKind::InvalidColor("(#0dc574)".into()),
+        );
+        assert_eq!(error, Some(expected));
+    }
+}
@@ -1,78 +1,140 @@
-use anyhow::{anyhow, bail, Context, Result};
+use crate::{
+    error::{self, Location},
+    Solution,
+};
+use anyhow::Result;
 use itertools::Itertools;
 use ranges::{self, GenericRange, OperationResult};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ops::{Bound, RangeBounds},
 };
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day19.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
-}
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+
+    type Input<'a> = System;
+    type Error = Error;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        let (workflows, parts) = input
+            .split_once("\n\n")
+            .ok_or_else(|| Error::new(Location::end_of(input), ErrorKind::MissingParts))?;
+        let workflows = parse_workflows(workflows)?;
+        let parts = parse_parts(parts)
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.offset_by(Location::of(input, parts)))?;
+        Ok(System { workflows, parts })
+    }
 
-fn part1(input: &str) -> Result<u64> {
-    let (workflows, parts) = input.split_once("\n\n").context("Error parsing input")?;
-    let workflows = parse_workflows(workflows)?;
-    let parts = parse_parts(parts);
+    fn part1(system: &Self::Input<'_>) -> Result<u64> {
+        let sum = system
+            .parts
+            .iter()
+            .filter(|part| is_part_accepted(part, &system.workflows))
+            .map(|part| part.x + part.m + part.a + part.s)
+            .sum();
+        Ok(sum)
+    }
 
-    parts
-        .filter_ok(|part| is_part_accepted(part, &workflows))
-        .map_ok(|part| part.x + part.m + part.a + part.s)
-        .sum()
+    fn part2(system: &Self::Input<'_>) -> Result<u64> {
+        let ranges = PartRanges {
+            x: (1..=4000).into(),
+            m: (1..=4000).into(),
+            a: (1..=4000).into(),
+            s: (1..=4000).into(),
+        };
+        Ok(count_accepted(ranges, "in", &system.workflows))
+    }
 }
 
-fn part2(input: &str) -> Result<u64> {
-    let (workflows, _) = input.split_once("\n\n").context("Error parsing input")?;
-    let workflows = parse_workflows(workflows)?;
-    let ranges = PartRanges {
-        x: (1..=4000).into(),
-        m: (1..=4000).into(),
-        a: (1..=4000).into(),
-        s: (1..=4000).into(),
-    };
-    Ok(count_accepted(ranges, "in", &workflows))
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected the parts after a blank line")]
+    MissingParts,
+    #[error("Expected a workflow like 'px{{a<2006:qkq,rfg}}', found '{0}'")]
+    InvalidWorkflow(String),
+    #[error("Invalid rule '{0}'")]
+    InvalidRule(String),
+    #[error("Unexpected category '{0}'")]
+    InvalidCategory(char),
+    #[error("Unexpected operator '{0}'")]
+    InvalidOperator(char),
+    #[error("Invalid rating '{0}'")]
+    InvalidRating(String),
+    #[error("Expected 4 ratings, found '{0}'")]
+    InvalidPart(String),
+    #[error("Workflow '{0}' is never defined")]
+    UnknownWorkflow(String),
+    #[error("Workflow '{0}' does not end with a rule that always applies")]
+    MissingFallback(String),
+    #[error("Workflow '{0}' can lead back to itself")]
+    Cycle(String),
+    #[error("No workflow named 'in'")]
+    MissingStart,
 }
 
-fn is_part_accepted(part: &Part, workflows: &HashMap<String, Workflow>) -> bool {
-    let mut output = workflows["in"].process(part);
+pub type Error = error::Error<ErrorKind>;
+
+pub struct System {
+    pub workflows: HashMap<String, Workflow>,
+    pub parts: Vec<Part>,
+}
+
+//workflows that are missing reject every part
+pub fn is_part_accepted(part: &Part, workflows: &HashMap<String, Workflow>) -> bool {
+    let mut output = Output::Workflow("in".to_string());
     loop {
         match output {
             Output::Accept => return true,
             Output::Reject => return false,
-            Output::Workflow(next) => output = workflows[&next].process(part),
+            Output::Workflow(next) => match workflows.get(&next) {
+                Some(workflow) => output = workflow.process(part),
+                None => return false,
+            },
         }
     }
 }
 
-fn parse_parts(parts: &str) -> impl Iterator<Item = Result<Part>> + '_ {
-    parts.lines().map(|line| {
+//locations in errors are relative to parts
+pub fn parse_parts(parts: &str) -> impl Iterator<Item = Result<Part, Error>> + '_ {
+    parts.lines().map(move |line| {
         let nums = line
             .split(',')
             .map(|category| {
-                category
+                let rating = category
                     .split(|c: char| !c.is_ascii_digit())
                     .find(|s| !s.is_empty())
-                    .context("Missing rating")?
-                    .parse()
-                    .map_err(Into::into)
+                    .unwrap_or(category);
+                //small enough that adding them up never overflows
+                rating.parse::<u32>().map(u64::from).map_err(|_| {
+                    Error::at(parts, rating, ErrorKind::InvalidRating(rating.to_string()))
+                })
             })
-            .collect::<Result<Vec<_>>>()?;
+            .collect::<Result<Vec<_>, _>>()?;
         match nums.as_slice() {
             &[x, m, a, s] => Ok(Part { x, m, a, s }),
-            _ => bail!("Expected 4 ratings for part. Actual={:?}", nums),
+            _ => Err(Error::at(
+                parts,
+                line,
+                ErrorKind::InvalidPart(line.to_string()),
+            )),
         }
     })
 }
 
-fn count_accepted(
+pub fn count_accepted(
     mut ranges: PartRanges,
     workflow_name: &str,
     workflows: &HashMap<String, Workflow>,
 ) -> u64 {
-    let workflow = &workflows[workflow_name];
+    let Some(workflow) = workflows.get(workflow_name) else {
+        return 0;
+    };
     workflow
         .rules
         .iter()
@@ -113,7 +175,7 @@ fn count_accepted(
 }
 
 #[derive(Clone)]
-enum Output {
+pub enum Output {
     Accept,
     Reject,
     Workflow(String),
@@ -129,22 +191,26 @@ impl From<&str> for Output {
     }
 }
 
-struct Part {
-    x: u64,
-    m: u64,
-    a: u64,
-    s: u64,
+pub struct Part {
+    pub x: u64,
+    pub m: u64,
+    pub a: u64,
+    pub s: u64,
 }
 
 #[derive(Clone)]
-struct PartRanges {
-    x: GenericRange<u64>,
-    m: GenericRange<u64>,
-    a: GenericRange<u64>,
-    s: GenericRange<u64>,
+pub struct PartRanges {
+    pub x: GenericRange<u64>,
+    pub m: GenericRange<u64>,
+    pub a: GenericRange<u64>,
+    pub s: GenericRange<u64>,
 }
 
 fn size(range: GenericRange<u64>) -> u64 {
+    if range.is_empty() {
+        return 0;
+    }
+
     let start = match range.start_bound() {
         Bound::Included(&num) => num,
         Bound::Excluded(&num) => num + 1,
@@ -161,7 +227,7 @@ fn size(range: GenericRange<u64>) -> u64 {
 }
 
 impl PartRanges {
-    fn get(&self, category: Category) -> GenericRange<u64> {
+    pub fn get(&self, category: Category) -> GenericRange<u64> {
         match category {
             Category::X => self.x,
             Category::M => self.m,
@@ -170,7 +236,7 @@ impl PartRanges {
         }
     }
 
-    fn get_mut(&mut self, category: Category) -> &mut GenericRange<u64> {
+    pub fn get_mut(&mut self, category: Category) -> &mut GenericRange<u64> {
         match category {
             Category::X => &mut self.x,
             Category::M => &mut self.m,
@@ -179,7 +245,7 @@ impl PartRanges {
         }
     }
 
-    fn count(&self) -> u64 {
+    pub fn count(&self) -> u64 {
         [self.x, self.m, self.a, self.s]
             .into_iter()
             .map(size)
@@ -188,7 +254,7 @@ impl PartRanges {
 }
 
 impl Part {
-    fn get(&self, category: Category) -> u64 {
+    pub fn get(&self, category: Category) -> u64 {
         match category {
             Category::X => self.x,
             Category::M => self.m,
@@ -198,12 +264,12 @@ impl Part {
     }
 }
 
-struct Workflow {
-    rules: Vec<Rule>,
+pub struct Workflow {
+    pub rules: Vec<Rule>,
 }
 
 impl Workflow {
-    fn process(&self, part: &Part) -> Output {
+    pub fn process(&self, part: &Part) -> Output {
         for rule in &self.rules {
             if let Some(category) = rule.category {
                 if rule.range.contains(&part.get(category)) {
@@ -214,84 +280,292 @@ impl Workflow {
             }
         }
 
-        panic!()
+        //parse_workflows makes sure the last rule always applies, so this is only for hand made workflows
+        Output::Reject
     }
 }
 
 #[derive(Clone, Copy)]
-enum Category {
+pub enum Category {
     X,
     M,
     A,
     S,
 }
 
-impl From<char> for Category {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Category {
+    type Error = ErrorKind;
+
+    fn try_from(value: char) -> Result<Self, ErrorKind> {
         match value {
-            'x' => Self::X,
-            'm' => Self::M,
-            'a' => Self::A,
-            's' => Self::S,
-            _ => panic!("Unexpected category: {}", value),
+            'x' => Ok(Self::X),
+            'm' => Ok(Self::M),
+            'a' => Ok(Self::A),
+            's' => Ok(Self::S),
+            _ => Err(ErrorKind::InvalidCategory(value)),
         }
     }
 }
 
-struct Rule {
-    range: GenericRange<u64>,
-    category: Option<Category>,
-    output: Output,
+pub struct Rule {
+    pub range: GenericRange<u64>,
+    pub category: Option<Category>,
+    pub output: Output,
 }
 
-fn parse_workflows(workflows: &str) -> Result<HashMap<String, Workflow>> {
-    workflows
+//also makes sure that following the workflows from 'in' always ends by accepting or rejecting
+pub fn parse_workflows(workflows: &str) -> Result<HashMap<String, Workflow>, Error> {
+    let error = |token: &str, kind| Error::at(workflows, token, kind);
+
+    let lines = workflows
         .lines()
         .map(|line| {
-            let (name, remaining) = line
-                .split_once('{')
-                .context(anyhow!("Error parsing workflow name from {}", line))?;
+            line.split_once('{')
+                .and_then(|(name, rules)| Some((name, rules.strip_suffix('}')?)))
+                .ok_or_else(|| error(line, ErrorKind::InvalidWorkflow(line.to_string())))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let names = lines.iter().map(|&(name, _)| name).collect::<HashSet<_>>();
+    if !names.contains("in") {
+        return Err(Error::new(Location::new(1, 1), ErrorKind::MissingStart));
+    }
 
-            let rules = parse_rules(remaining)?;
+    let parsed = lines
+        .into_iter()
+        .map(|(name, rules)| {
+            let rules = rules
+                .split(',')
+                .map(|token| {
+                    let rule = parse_rule(workflows, token)?;
+                    let output = token.rsplit(':').next().unwrap_or(token);
+                    match &rule.output {
+                        Output::Workflow(next) if !names.contains(next.as_str()) => {
+                            Err(error(output, ErrorKind::UnknownWorkflow(next.to_string())))
+                        }
+                        _ => Ok(rule),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if rules.last().is_some_and(|rule| rule.category.is_some()) {
+                return Err(error(name, ErrorKind::MissingFallback(name.to_string())));
+            }
+            Ok((name, Workflow { rules }))
+        })
+        .collect::<Result<HashMap<_, _>, _>>()?;
 
-            let workflow = Workflow { rules };
+    if let Some(name) = find_cycle(&parsed) {
+        return Err(error(name, ErrorKind::Cycle(name.to_string())));
+    }
 
-            Ok((name.to_string(), workflow))
-        })
-        .collect()
+    let workflows = parsed
+        .into_iter()
+        .map(|(name, workflow)| (name.to_string(), workflow))
+        .collect();
+    Ok(workflows)
 }
 
-fn parse_rules(remaining: &str) -> Result<Vec<Rule>> {
-    remaining
-        .split(',')
-        .map(|token| {
-            if let Some((condition, output)) = token.split_once(':') {
-                let mut condition = condition.chars();
-                let category = condition.next(); //can be one of x,m,a,s
-                let cmp = condition.next(); // < or >
-                let (category, cmp) = category
-                    .zip(cmp)
-                    .context(anyhow!("Error parsing rule: {}", token))?;
-                let num = condition.as_str().parse()?;
-
-                let range = match cmp {
-                    '<' => GenericRange::new_less_than(num),
-                    '>' => GenericRange::new_greater_than(num),
-                    _ => bail!("Unexpected operator: {}", cmp),
-                };
-                Ok(Rule {
-                    range,
-                    category: Some(category.into()),
-                    output: output.into(),
-                })
-            } else {
-                let token = token.replace('}', "");
-                Ok(Rule {
-                    range: (..).into(),
-                    category: None,
-                    output: token.as_str().into(),
-                })
+//locations in errors are relative to text, which the token is a slice of
+fn parse_rule(text: &str, token: &str) -> Result<Rule, Error> {
+    let error = |at: &str, kind| Error::at(text, at, kind);
+
+    let Some((condition, output)) = token.split_once(':') else {
+        return Ok(Rule {
+            range: (..).into(),
+            category: None,
+            output: token.into(),
+        });
+    };
+
+    let mut chars = condition.char_indices();
+    let ((_, category), (i, cmp)) = chars
+        .next()
+        .zip(chars.next())
+        .ok_or_else(|| error(token, ErrorKind::InvalidRule(token.to_string())))?;
+    let category = Category::try_from(category).map_err(|kind| error(condition, kind))?;
+    let num = &condition[i + cmp.len_utf8()..];
+    //small enough that the ranges never overflow
+    let num = num
+        .parse::<u32>()
+        .map(u64::from)
+        .map_err(|_| error(num, ErrorKind::InvalidRating(num.to_string())))?;
+
+    let range = match cmp {
+        '<' => GenericRange::new_less_than(num),
+        '>' => GenericRange::new_greater_than(num),
+        _ => return Err(error(&condition[i..], ErrorKind::InvalidOperator(cmp))),
+    };
+    Ok(Rule {
+        range,
+        category: Some(category),
+        output: output.into(),
+    })
+}
+
+//a workflow that can lead back to itself, if there is one.
+//repeatedly removes workflows that nothing leads to, so whatever is left is part of a cycle.
+fn find_cycle<'a>(workflows: &HashMap<&'a str, Workflow>) -> Option<&'a str> {
+    let next_workflows = |workflow: &'a str| {
+        workflows[workflow]
+            .rules
+            .iter()
+            .filter_map(|rule| match &rule.output {
+                Output::Workflow(next) => workflows.get_key_value(next.as_str()),
+                _ => None,
+            })
+            .map(|(&name, _)| name)
+    };
+
+    let mut incoming = workflows
+        .keys()
+        .map(|&name| (name, 0))
+        .collect::<HashMap<_, _>>();
+    for &name in workflows.keys() {
+        for next in next_workflows(name) {
+            *incoming.entry(next).or_default() += 1;
+        }
+    }
+
+    let mut unreached = incoming
+        .iter()
+        .filter(|&(_, &count)| count == 0)
+        .map(|(&name, _)| name)
+        .collect_vec();
+    while let Some(name) = unreached.pop() {
+        for next in next_workflows(name) {
+            let count = incoming.entry(next).or_default();
+            *count -= 1;
+            if *count == 0 {
+                unreached.push(next);
             }
+        }
+    }
+
+    incoming
+        .into_iter()
+        .filter(|&(_, count)| count > 0)
+        .map(|(name, _)| name)
+        .min_by_key(|name| name.as_ptr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2005,s=1013}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day19::solve_part1(EXAMPLE)?, 19114);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day19::solve_part2(EXAMPLE)?, 167409079868000);
+        Ok(())
+    }
+
+    #[test]
+    fn rule_covering_whole_range() -> Result<()> {
+        //after the first rule, x<3000 matches every part that is left
+        let input = "in{x>2000:R,x<3000:A,R}\n\n{x=1,m=1,a=1,s=1}";
+        assert_eq!(Day19::solve_part2(input)?, 2000 * 4000 * 4000 * 4000);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_workflows() {
+        let error = |input| Day19::parse(input).err();
+
+        let expected = Error::new(Location::new(2, 1), ErrorKind::Cycle("ab".into()));
+        assert_eq!(
+            error("in{x<5:ab,R}\nab{cd}\ncd{m>3:ab,A}\n\n"),
+            Some(expected)
+        );
+
+        let expected = Error::new(Location::new(1, 8), ErrorKind::UnknownWorkflow("ab".into()));
+        assert_eq!(error("in{x<5:ab,R}\n\n"), Some(expected));
+
+        let expected = Error::new(Location::new(1, 4), ErrorKind::InvalidCategory('z'));
+        assert_eq!(error("in{z<5:A,R}\n\n"), Some(expected));
+    }
+
+    //every rating goes from 1 to MAX_RATING, so every part can be checked one by one
+    const MAX_RATING: u64 = 5;
+
+    //workflows only lead to workflows that come after them, so there are no cycles
+    fn workflows() -> impl Strategy<Value = String> {
+        let rule = (
+            prop::sample::select(vec!['x', 'm', 'a', 's']),
+            prop::sample::select(vec!['<', '>']),
+            0..=MAX_RATING + 1,
+            any::<usize>(),
+        );
+        let workflow = (prop::collection::vec(rule, 0..4), any::<usize>());
+        prop::collection::vec(workflow, 1..6).prop_map(|workflows| {
+            let count = workflows.len();
+            let name = |i: usize| {
+                if i == 0 {
+                    "in".to_string()
+                } else {
+                    format!("w{}", i)
+                }
+            };
+            let output = |i: usize, choice: usize| match choice % (count - i + 1) {
+                0 => "A".to_string(),
+                1 => "R".to_string(),
+                next => name(i + next - 1),
+            };
+            workflows
+                .into_iter()
+                .enumerate()
+                .map(|(i, (rules, fallback))| {
+                    let rules = rules
+                        .into_iter()
+                        .map(|(category, cmp, num, choice)| {
+                            format!("{}{}{}:{}", category, cmp, num, output(i, choice))
+                        })
+                        .chain([output(i, fallback)])
+                        .join(",");
+                    format!("{}{{{}}}", name(i), rules)
+                })
+                .join("\n")
         })
-        .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn matches_enumeration(workflows in workflows()) {
+            let workflows = parse_workflows(&workflows)?;
+            let ranges = PartRanges {
+                x: (1..=MAX_RATING).into(),
+                m: (1..=MAX_RATING).into(),
+                a: (1..=MAX_RATING).into(),
+                s: (1..=MAX_RATING).into(),
+            };
+            let ratings = || 1..=MAX_RATING;
+            let accepted = itertools::iproduct!(ratings(), ratings(), ratings(), ratings())
+                .filter(|&(x, m, a, s)| is_part_accepted(&Part { x, m, a, s }, &workflows))
+                .count();
+            prop_assert_eq!(count_accepted(ranges, "in", &workflows), accepted as u64);
+        }
+    }
 }
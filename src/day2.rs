@@ -1,112 +1,192 @@
-use anyhow::{bail, Context, Result};
+use crate::{
+    error::{self, Location},
+    stream::{self, Streaming},
+    Solution,
+};
+use anyhow::Result;
 use itertools::Itertools;
+use std::io::BufRead;
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day2.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input<'a> = Vec<Game>;
+    type Error = Error;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_game(line).map_err(|e| e.offset_by(Location::new(i + 1, 1))))
+            .collect()
+    }
+
+    fn part1(games: &Self::Input<'_>) -> Result<u64> {
+        let sum = games
+            .iter()
+            .filter(|game| game.is_possible())
+            .map(|game| game.id as u64)
+            .sum();
+        Ok(sum)
+    }
+
+    fn part2(games: &Self::Input<'_>) -> Result<u64> {
+        let sum = games.iter().map(|game| game.fewest_cubes().product()).sum();
+        Ok(sum)
+    }
+}
+
+impl Streaming for Day2 {
+    fn stream_part1(input: impl BufRead) -> Result<u64> {
+        stream::parse_lines(input, parse_game)
+            .filter_ok(Game::is_possible)
+            .map_ok(|game| game.id as u64)
+            .sum()
+    }
+
+    fn stream_part2(input: impl BufRead) -> Result<u64> {
+        stream::parse_lines(input, parse_game)
+            .map_ok(|game| game.fewest_cubes().product())
+            .sum()
+    }
 }
 
 #[derive(Default)]
-struct Sample {
-    red: u32,
-    green: u32,
-    blue: u32,
+pub struct Sample {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
 }
 
 impl Sample {
-    fn product(&self) -> u32 {
-        self.red * self.green * self.blue
+    pub fn product(&self) -> u64 {
+        self.red as u64 * self.green as u64 * self.blue as u64
     }
 }
 
-struct Game {
-    id: u32,
-    samples: Vec<Sample>,
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected ':' after the game id")]
+    MissingColon,
+    #[error("Invalid game id '{0}'")]
+    InvalidId(String),
+    #[error("Expected a count and a color, found '{0}'")]
+    InvalidCube(String),
+    #[error("Invalid cube count '{0}'")]
+    InvalidCount(String),
+    #[error("Unexpected color '{0}'")]
+    UnknownColor(String),
 }
 
-fn parse_game(line: &str) -> Result<Game> {
-    let (id, remaining) = line.split_once(':').context("No \":\" in line.")?;
+pub type Error = error::Error<ErrorKind>;
+
+pub struct Game {
+    pub id: u32,
+    pub samples: Vec<Sample>,
+}
+
+impl Game {
+    pub fn is_possible(&self) -> bool {
+        let max_red = 12;
+        let max_green = 13;
+        let max_blue = 14;
+
+        self.samples.iter().all(|&Sample { red, green, blue }| {
+            red <= max_red && green <= max_green && blue <= max_blue
+        })
+    }
+
+    //the fewest cubes of each color that could have been in the bag
+    pub fn fewest_cubes(&self) -> Sample {
+        self.samples.iter().fold(
+            Sample::default(),
+            |Sample {
+                 red: max_red,
+                 green: max_green,
+                 blue: max_blue,
+             },
+             &Sample { red, green, blue }| Sample {
+                red: max_red.max(red),
+                green: max_green.max(green),
+                blue: max_blue.max(blue),
+            },
+        )
+    }
+}
+
+//locations in errors are relative to the line
+pub fn parse_game(line: &str) -> Result<Game, Error> {
+    let error = |token: &str, kind| Error::at(line, token, kind);
+
+    let (id, remaining) = line
+        .split_once(':')
+        .ok_or_else(|| Error::new(Location::end_of(line), ErrorKind::MissingColon))?;
     let id = id
-        .split_once(' ')
-        .context("Error parsing id")?
-        .1
-        .parse::<u32>()?;
+        .strip_prefix("Game ")
+        .and_then(|number| number.parse().ok())
+        .ok_or_else(|| error(id, ErrorKind::InvalidId(id.to_string())))?;
 
     let samples = remaining
         .split(';')
         .map(|sample| {
-            sample
-                .split(',')
-                .map(|cube| {
-                    let (count, color) = cube
-                        .trim()
-                        .split_once(' ')
-                        .context("Error parsing cube count")?;
-                    let count = count.parse()?;
-                    Ok((count, color))
-                })
-                .try_fold(Sample::default(), |acc, cube: Result<_>| {
-                    let (count, color) = cube?;
-                    match color {
-                        "red" => Ok(Sample { red: count, ..acc }),
-                        "green" => Ok(Sample {
-                            green: count,
-                            ..acc
-                        }),
-                        "blue" => Ok(Sample { blue: count, ..acc }),
-                        _ => bail!("Unexpected color: {}", color),
-                    }
-                })
+            sample.split(',').try_fold(Sample::default(), |acc, cube| {
+                let cube = cube.trim();
+                let (count, color) = cube
+                    .split_once(' ')
+                    .ok_or_else(|| error(cube, ErrorKind::InvalidCube(cube.to_string())))?;
+                let count = count
+                    .parse()
+                    .map_err(|_| error(count, ErrorKind::InvalidCount(count.to_string())))?;
+                match color {
+                    "red" => Ok(Sample { red: count, ..acc }),
+                    "green" => Ok(Sample {
+                        green: count,
+                        ..acc
+                    }),
+                    "blue" => Ok(Sample { blue: count, ..acc }),
+                    _ => Err(error(color, ErrorKind::UnknownColor(color.to_string()))),
+                }
+            })
         })
-        .collect::<Result<_>>()?;
+        .collect::<Result<_, _>>()?;
 
     Ok(Game { samples, id })
 }
 
-fn part1(input: &str) -> Result<u32> {
-    let max_red = 12;
-    let max_green = 13;
-    let max_blue = 14;
-
-    input
-        .lines()
-        .map(parse_game)
-        .filter_map_ok(|Game { id, samples }| {
-            for Sample { red, green, blue } in samples {
-                if red > max_red || green > max_green || blue > max_blue {
-                    return None;
-                }
-            }
-            Some(id)
-        })
-        .sum()
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-fn part2(input: &str) -> Result<u32> {
-    input
-        .lines()
-        .map(parse_game)
-        .map_ok(|Game { samples, .. }| {
-            samples
-                .into_iter()
-                .fold(
-                    Sample::default(),
-                    |Sample {
-                         red: max_red,
-                         green: max_green,
-                         blue: max_blue,
-                     },
-                     Sample { red, green, blue }| {
-                        Sample {
-                            red: max_red.max(red),
-                            green: max_green.max(green),
-                            blue: max_blue.max(blue),
-                        }
-                    },
-                )
-                .product()
-        })
-        .sum()
+    const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day2::solve_part1(EXAMPLE)?, 8);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day2::solve_part2(EXAMPLE)?, 2286);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_color() {
+        let error = Day2::parse("Game 1: 3 blue\nGame 2: 1 blue, 2 purple").err();
+        let expected = Error::new(
+            Location::new(2, 19),
+            ErrorKind::UnknownColor("purple".into()),
+        );
+        assert_eq!(error, Some(expected));
+    }
 }
@@ -0,0 +1,393 @@
+use crate::{
+    error::{self, Location},
+    Solution,
+};
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use num_integer::Integer;
+use std::collections::{HashMap, VecDeque};
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+
+    type Input<'a> = Network<'a>;
+    type Error = Error;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        parse_network(input)
+    }
+
+    fn part1(network: &Self::Input<'_>) -> Result<u64> {
+        let (low, high) = count_pulses(network, 1000)?;
+        Ok(low * high)
+    }
+
+    fn part2(network: &Self::Input<'_>) -> Result<u64> {
+        presses_until_low(network, "rx")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected a module like '%a -> b, c', found '{0}'")]
+    InvalidLine(String),
+    #[error("Expected '%' or '&' before the module name, found '{0}'")]
+    InvalidModule(String),
+    #[error("Invalid module name '{0}'")]
+    InvalidName(String),
+    #[error("Module '{0}' is defined more than once")]
+    DuplicateModule(String),
+    #[error("No module named 'broadcaster'")]
+    MissingBroadcaster,
+}
+
+pub type Error = error::Error<ErrorKind>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Pulse {
+    Low,
+    High,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kind {
+    Broadcaster,
+    FlipFlop,
+    Conjunction,
+    //only ever receives pulses, like rx
+    Output,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Module {
+    pub kind: Kind,
+    pub inputs: Vec<usize>,
+    pub destinations: Vec<usize>,
+}
+
+//Modules are referred to by their index
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Network<'a> {
+    pub names: Vec<&'a str>,
+    pub modules: Vec<Module>,
+    pub broadcaster: usize,
+}
+
+impl Network<'_> {
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|&other| other == name)
+    }
+}
+
+//give up on a press after this many pulses
+const MAX_PULSES: usize = 1_000_000;
+
+//what every flip-flop and conjunction remembers between pulses
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct State {
+    //whether each flip-flop is on
+    on: Vec<bool>,
+    //the last pulse each conjunction received from each of its inputs, in the order of Module::inputs
+    memory: Vec<Vec<Pulse>>,
+}
+
+impl State {
+    //every flip-flop off and every conjunction remembering low pulses
+    pub fn new(network: &Network) -> Self {
+        State {
+            on: vec![false; network.modules.len()],
+            memory: network
+                .modules
+                .iter()
+                .map(|module| vec![Pulse::Low; module.inputs.len()])
+                .collect(),
+        }
+    }
+
+    //Pushes the button once, calling on_pulse with (from, to, pulse) for every pulse
+    //in the order they are sent. The button itself is from usize::MAX.
+    //Fails if the pulses are still going after MAX_PULSES, since loops can keep them going forever.
+    pub fn press(
+        &mut self,
+        network: &Network,
+        mut on_pulse: impl FnMut(usize, usize, Pulse),
+    ) -> Result<()> {
+        let mut queue = VecDeque::from([(usize::MAX, network.broadcaster, Pulse::Low)]);
+        let mut sent_count = 0;
+        while let Some((from, to, pulse)) = queue.pop_front() {
+            sent_count += 1;
+            if sent_count > MAX_PULSES {
+                bail!("A single press sent more than {} pulses", MAX_PULSES);
+            }
+            on_pulse(from, to, pulse);
+            let module = &network.modules[to];
+            let sent = match module.kind {
+                Kind::Broadcaster => Some(pulse),
+                Kind::FlipFlop if pulse == Pulse::Low => {
+                    self.on[to] = !self.on[to];
+                    Some(if self.on[to] { Pulse::High } else { Pulse::Low })
+                }
+                Kind::FlipFlop => None,
+                Kind::Conjunction => {
+                    let memory = &mut self.memory[to];
+                    if let Some(input) = module.inputs.iter().position(|&input| input == from) {
+                        memory[input] = pulse;
+                    }
+                    if memory.iter().all(|&pulse| pulse == Pulse::High) {
+                        Some(Pulse::Low)
+                    } else {
+                        Some(Pulse::High)
+                    }
+                }
+                Kind::Output => None,
+            };
+            if let Some(sent) = sent {
+                queue.extend(
+                    module
+                        .destinations
+                        .iter()
+                        .map(|&destination| (to, destination, sent)),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_network(input: &str) -> Result<Network<'_>, Error> {
+    let error = |token: &str, kind| Error::at(input, token, kind);
+    let is_name = |name: &str| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric());
+
+    let lines = input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (module, destinations) = line
+                .split_once(" -> ")
+                .ok_or_else(|| error(line, ErrorKind::InvalidLine(line.to_string())))?;
+            let (kind, name) = match module {
+                "broadcaster" => (Kind::Broadcaster, module),
+                _ if module.starts_with('%') => (Kind::FlipFlop, &module[1..]),
+                _ if module.starts_with('&') => (Kind::Conjunction, &module[1..]),
+                _ => return Err(error(module, ErrorKind::InvalidModule(module.to_string()))),
+            };
+            let destinations = destinations
+                .split(',')
+                .map(str::trim)
+                .map(|destination| {
+                    if is_name(destination) {
+                        Ok(destination)
+                    } else {
+                        Err(error(
+                            destination,
+                            ErrorKind::InvalidName(destination.to_string()),
+                        ))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if !is_name(name) {
+                return Err(error(name, ErrorKind::InvalidName(name.to_string())));
+            }
+            Ok((name, kind, destinations))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    //modules that are only ever sent pulses come after the defined ones
+    let mut indices = HashMap::new();
+    for &(name, _, _) in &lines {
+        if indices.insert(name, indices.len()).is_some() {
+            return Err(error(name, ErrorKind::DuplicateModule(name.to_string())));
+        }
+    }
+    let mut names = lines.iter().map(|&(name, _, _)| name).collect_vec();
+    let mut modules = lines
+        .iter()
+        .map(|&(_, kind, _)| Module {
+            kind,
+            inputs: Vec::new(),
+            destinations: Vec::new(),
+        })
+        .collect_vec();
+
+    for (from, (_, _, destinations)) in lines.iter().enumerate() {
+        for &destination in destinations {
+            let to = *indices.entry(destination).or_insert_with(|| {
+                names.push(destination);
+                modules.push(Module {
+                    kind: Kind::Output,
+                    inputs: Vec::new(),
+                    destinations: Vec::new(),
+                });
+                names.len() - 1
+            });
+            modules[from].destinations.push(to);
+            modules[to].inputs.push(from);
+        }
+    }
+
+    let broadcaster = lines
+        .iter()
+        .position(|&(_, kind, _)| kind == Kind::Broadcaster)
+        .ok_or_else(|| Error::new(Location::end_of(input), ErrorKind::MissingBroadcaster))?;
+
+    Ok(Network {
+        names,
+        modules,
+        broadcaster,
+    })
+}
+
+//the number of low and high pulses sent over the given number of presses
+pub fn count_pulses(network: &Network, presses: usize) -> Result<(u64, u64)> {
+    let mut state = State::new(network);
+    let (mut low, mut high) = (0, 0);
+    for _ in 0..presses {
+        state.press(network, |_, _, pulse| match pulse {
+            Pulse::Low => low += 1,
+            Pulse::High => high += 1,
+        })?;
+    }
+    Ok((low, high))
+}
+
+//give up on finding the periods after this many presses
+const MAX_PRESSES: u64 = 100_000;
+
+//The fewest presses until the given module is sent a low pulse.
+//This assumes the module is fed by a single conjunction whose inputs each send it a high pulse
+//once every so many presses, so the answer is when all of those periods line up.
+pub fn presses_until_low(network: &Network, target: &str) -> Result<u64> {
+    let target = network
+        .find(target)
+        .with_context(|| format!("No module sends pulses to {}", target))?;
+    let Ok(&feeder) = network.modules[target].inputs.iter().exactly_one() else {
+        bail!("Expected a single module to send pulses to the target");
+    };
+    if network.modules[feeder].kind != Kind::Conjunction {
+        bail!("Expected the target to be fed by a conjunction");
+    }
+    let inputs = &network.modules[feeder].inputs;
+
+    //the presses on which each input sent a high pulse to the feeder, up to the second one
+    let mut highs = vec![Vec::new(); inputs.len()];
+    let mut state = State::new(network);
+    for press in 1..=MAX_PRESSES {
+        state.press(network, |from, to, pulse| {
+            if to == feeder && pulse == Pulse::High {
+                if let Some(i) = inputs.iter().position(|&input| input == from) {
+                    if highs[i].len() < 2 && highs[i].last() != Some(&press) {
+                        highs[i].push(press);
+                    }
+                }
+            }
+        })?;
+        if highs.iter().all(|presses| presses.len() == 2) {
+            break;
+        }
+    }
+
+    let periods = highs
+        .iter()
+        .zip(inputs)
+        .map(|(presses, &input)| match presses[..] {
+            [first, second] if second == 2 * first => Ok(first),
+            [_, _] => bail!(
+                "{} does not send high pulses periodically",
+                network.names[input]
+            ),
+            _ => bail!(
+                "{} did not send enough high pulses within {} presses",
+                network.names[input],
+                MAX_PRESSES
+            ),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    periods
+        .into_iter()
+        .try_fold(1_u64, |presses, period| {
+            presses.checked_mul(period / presses.gcd(&period))
+        })
+        .context("Too many presses to count")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE1: &str = "\
+broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a";
+
+    const EXAMPLE2: &str = "\
+broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output";
+
+    //two 4 bit counters that reset after 11 and 13 presses, joined by inverters into the conjunction feeding rx
+    const COUNTERS: &str = "\
+broadcaster -> a0, b0
+%a0 -> a1, ac
+%a1 -> a2, ac
+%a2 -> a3
+%a3 -> ac
+&ac -> a0, a2, ai
+&ai -> join
+%b0 -> b1, bc
+%b1 -> b2
+%b2 -> b3, bc
+%b3 -> bc
+&bc -> b0, b1, bi
+&bi -> join
+&join -> rx";
+
+    #[test]
+    fn part1_examples() -> Result<()> {
+        assert_eq!(Day20::solve_part1(EXAMPLE1)?, 32000000);
+        assert_eq!(Day20::solve_part1(EXAMPLE2)?, 11687500);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_counters() -> Result<()> {
+        assert_eq!(Day20::solve_part2(COUNTERS)?, 143);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_modules() {
+        let error = |input| Day20::parse(input).err();
+
+        let expected = Error::new(Location::new(2, 1), ErrorKind::InvalidModule("a".into()));
+        assert_eq!(error("broadcaster -> a\na -> b"), Some(expected));
+
+        let expected = Error::new(Location::new(3, 2), ErrorKind::DuplicateModule("a".into()));
+        assert_eq!(error("broadcaster -> a\n%a -> b\n&a -> b"), Some(expected));
+
+        let expected = Error::new(Location::new(1, 8), ErrorKind::MissingBroadcaster);
+        assert_eq!(error("%a -> b"), Some(expected));
+    }
+
+    #[test]
+    fn endless_pulses() {
+        for input in [
+            "broadcaster -> a\n&a -> a",
+            "broadcaster -> broadcaster",
+            "broadcaster -> a\n&a -> a, a",
+            "broadcaster -> a\n%a -> c\n&c -> a, a",
+        ] {
+            let error = Day20::solve_part1(input).err().map(|e| e.to_string());
+            let expected = format!("A single press sent more than {} pulses", MAX_PULSES);
+            assert_eq!(error, Some(expected), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{
+    error::{self, Location},
+    geometry::{Direction, Point},
+    grid::{self, Grid},
+    Solution,
+};
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use std::collections::HashSet;
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+
+    type Input<'a> = Garden;
+    type Error = Error;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        parse_garden(input)
+    }
+
+    fn part1(garden: &Self::Input<'_>) -> Result<u64> {
+        Ok(count_reachable(garden, 64))
+    }
+
+    fn part2(garden: &Self::Input<'_>) -> Result<u64> {
+        count_reachable_tiled(garden, 26501365)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error(transparent)]
+    Grid(grid::ErrorKind),
+    #[error("Expected at least one row and column")]
+    Empty,
+    #[error("No start in input")]
+    NoStart,
+    #[error("More than one start in input")]
+    ExtraStart,
+}
+
+pub type Error = error::Error<ErrorKind>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tile {
+    Plot,
+    Rock,
+}
+
+pub struct Garden {
+    pub grid: Grid<Tile>,
+    pub start: Point,
+}
+
+impl Garden {
+    //If tiled, the garden repeats forever in every direction.
+    //Otherwise, everything outside of it is rock.
+    pub fn is_plot(&self, point: Point, tiled: bool) -> bool {
+        let rows = self.grid.row_count() as isize;
+        let cols = self.grid.col_count() as isize;
+        let point = if tiled {
+            Point::new(point.x.rem_euclid(cols), point.y.rem_euclid(rows))
+        } else {
+            point
+        };
+        point
+            .to_position()
+            .and_then(|position| self.grid.get(position))
+            .is_some_and(|&tile| tile == Tile::Plot)
+    }
+
+    pub fn walk(&self, tiled: bool) -> Walk<'_> {
+        Walk {
+            garden: self,
+            tiled,
+            visited: HashSet::from([self.start]),
+            frontier: vec![self.start],
+            reached: [1, 0],
+            steps: 0,
+        }
+    }
+}
+
+//Walks outwards from the start, one step at a time.
+//Yields the number of plots that can be reached in exactly 0, 1, 2... steps.
+pub struct Walk<'a> {
+    garden: &'a Garden,
+    tiled: bool,
+    visited: HashSet<Point>,
+    //the plots first reached on the last step
+    frontier: Vec<Point>,
+    //the number of plots first reached after an even and an odd number of steps.
+    //any plot reached after n steps can be reached again after n + 2 by stepping back and forth.
+    reached: [u64; 2],
+    steps: usize,
+}
+
+impl Iterator for Walk<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let count = self.reached[self.steps % 2];
+        self.frontier = self
+            .frontier
+            .iter()
+            .flat_map(|&point| Direction::iter().map(move |direction| point.step(direction)))
+            .filter(|&point| self.garden.is_plot(point, self.tiled))
+            .filter(|&point| self.visited.insert(point))
+            .collect();
+        self.steps += 1;
+        self.reached[self.steps % 2] += self.frontier.len() as u64;
+        Some(count)
+    }
+}
+
+pub fn parse_garden(input: &str) -> Result<Garden, Error> {
+    //the start is a plot too
+    let grid = Grid::parse(input, |c| match c {
+        '.' | 'S' => Some(Tile::Plot),
+        '#' => Some(Tile::Rock),
+        _ => None,
+    })
+    .map_err(|e| e.map(ErrorKind::Grid))?;
+    if grid.row_count() == 0 || grid.col_count() == 0 {
+        return Err(Error::new(Location::new(1, 1), ErrorKind::Empty));
+    }
+
+    let mut starts = input.lines().enumerate().flat_map(|(row, line)| {
+        line.chars()
+            .positions(|c| c == 'S')
+            .map(move |col| (row, col))
+    });
+    let start = starts
+        .next()
+        .ok_or_else(|| Error::new(Location::new(1, 1), ErrorKind::NoStart))?;
+    if let Some(extra) = starts.next() {
+        return Err(Error::new(extra, ErrorKind::ExtraStart));
+    }
+
+    Ok(Garden {
+        grid,
+        start: start.into(),
+    })
+}
+
+//the plots that can be reached in exactly the given number of steps
+pub fn count_reachable(garden: &Garden, steps: usize) -> u64 {
+    garden.walk(false).nth(steps).unwrap_or(0)
+}
+
+//how many whole gardens to walk across while waiting for the growth to become quadratic
+const MAX_SAMPLES: usize = 100;
+//how many times in a row the growth has to look quadratic before trusting it
+const STABLE_SAMPLES: usize = 3;
+
+//Like count_reachable, but on the tiled garden and for far too many steps to walk.
+//Walking one more garden's width reaches a ring of gardens one wider than the last, so after a while
+//the number of plots reached every garden's width grows quadratically. That is extrapolated to the given steps.
+pub fn count_reachable_tiled(garden: &Garden, steps: usize) -> Result<u64> {
+    let size = garden.grid.row_count();
+    if garden.grid.col_count() != size {
+        bail!("Expected a square garden");
+    }
+    let (widths, offset) = (steps / size, steps % size);
+
+    //the plots reached after offset, offset + size, offset + 2 * size... steps
+    let mut samples = Vec::new();
+    for (width, count) in garden
+        .walk(true)
+        .skip(offset)
+        .step_by(size)
+        .enumerate()
+        .take(MAX_SAMPLES)
+    {
+        if width == widths {
+            return Ok(count);
+        }
+        samples.push(count as i128);
+
+        let second_differences = samples
+            .iter()
+            .tuple_windows()
+            .map(|(a, b, c)| c - 2 * b + a)
+            .collect_vec();
+        if second_differences.len() < STABLE_SAMPLES
+            || !second_differences[second_differences.len() - STABLE_SAMPLES..]
+                .iter()
+                .all_equal()
+        {
+            continue;
+        }
+
+        let (before, last) = (samples[samples.len() - 2], samples[samples.len() - 1]);
+        let second = second_differences[second_differences.len() - 1];
+        let first = last - before;
+        let remaining = (widths - width) as i128;
+        let count = last + remaining * first + remaining * (remaining + 1) / 2 * second;
+        return u64::try_from(count).context("Too many plots to count");
+    }
+    bail!(
+        "The plots reached did not grow quadratically within {} gardens",
+        MAX_SAMPLES
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        let garden = Day21::parse(EXAMPLE)?;
+        assert_eq!(count_reachable(&garden, 6), 16);
+        Ok(())
+    }
+
+    #[test]
+    fn tiled_examples() -> Result<()> {
+        let garden = Day21::parse(EXAMPLE)?;
+        let counts = garden.walk(true).take(501).collect_vec();
+        for (steps, count) in [(6, 16), (10, 50), (50, 1594), (100, 6536), (500, 167004)] {
+            assert_eq!(counts[steps], count);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn extrapolated_examples() -> Result<()> {
+        let garden = Day21::parse(EXAMPLE)?;
+        for (steps, count) in [(100, 6536), (500, 167004), (1000, 668697), (5000, 16733044)] {
+            assert_eq!(count_reachable_tiled(&garden, steps)?, count);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_starts() {
+        let error = Day21::parse("..\n..").err();
+        assert_eq!(
+            error,
+            Some(Error::new(Location::new(1, 1), ErrorKind::NoStart))
+        );
+
+        let error = Day21::parse("S.\n.S").err();
+        assert_eq!(
+            error,
+            Some(Error::new(Location::new(2, 2), ErrorKind::ExtraStart))
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{
+    error::{self, Location},
+    Solution,
+};
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use std::collections::{HashMap, VecDeque};
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+
+    type Input<'a> = Vec<Brick>;
+    type Error = Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_brick(line).map_err(|e| e.offset_by(Location::new(i + 1, 1))))
+            .collect()
+    }
+
+    fn part1(bricks: &Self::Input<'_>) -> Result<usize> {
+        let (_, graph) = settle(bricks)?;
+        Ok(graph.safe_to_disintegrate().count())
+    }
+
+    fn part2(bricks: &Self::Input<'_>) -> Result<usize> {
+        let (_, graph) = settle(bricks)?;
+        Ok((0..graph.len())
+            .map(|brick| graph.count_falling(brick))
+            .sum())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected a brick like '1,0,1~1,2,1', found '{0}'")]
+    InvalidBrick(String),
+    #[error("Invalid coordinate '{0}'")]
+    InvalidCoordinate(String),
+    #[error("Brick '{0}' has more than {MAX_CUBES} cubes")]
+    TooManyCubes(String),
+}
+
+pub type Error = error::Error<ErrorKind>;
+
+//far past anything in a real input, but few enough that every brick can be walked cube by cube
+pub const MAX_CUBES: u64 = 1000;
+
+//The cubes from start to end, inclusive. Every coordinate of start is at most that of end.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Brick {
+    pub start: [u32; 3],
+    pub end: [u32; 3],
+}
+
+impl Brick {
+    pub fn bottom(&self) -> u32 {
+        self.start[2]
+    }
+
+    pub fn height(&self) -> u32 {
+        self.end[2] - self.start[2] + 1
+    }
+
+    //the (x, y) of every cube seen from above
+    pub fn footprint(&self) -> impl Iterator<Item = (u32, u32)> {
+        (self.start[0]..=self.end[0]).cartesian_product(self.start[1]..=self.end[1])
+    }
+
+    //None if the top would be too high to fit
+    fn moved_to(&self, bottom: u32) -> Option<Brick> {
+        let mut brick = *self;
+        brick.start[2] = bottom;
+        brick.end[2] = bottom.checked_add(self.height() - 1)?;
+        Some(brick)
+    }
+}
+
+//locations in errors are relative to the line
+pub fn parse_brick(line: &str) -> Result<Brick, Error> {
+    let invalid = || Error::at(line, line, ErrorKind::InvalidBrick(line.to_string()));
+    let parse_end = |end: &str| -> Result<[u32; 3], Error> {
+        let coordinates = end
+            .split(',')
+            .map(|coordinate| {
+                coordinate.trim().parse().map_err(|_| {
+                    Error::at(
+                        line,
+                        coordinate,
+                        ErrorKind::InvalidCoordinate(coordinate.to_string()),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        coordinates.try_into().map_err(|_| invalid())
+    };
+
+    let (start, end) = line.split_once('~').ok_or_else(invalid)?;
+    let (start, end) = (parse_end(start)?, parse_end(end)?);
+    let brick = Brick {
+        start: [0, 1, 2].map(|i| start[i].min(end[i])),
+        end: [0, 1, 2].map(|i| start[i].max(end[i])),
+    };
+    let cubes = (0..3)
+        .map(|i| u64::from(brick.end[i] - brick.start[i]) + 1)
+        .try_fold(1_u64, |cubes, length| cubes.checked_mul(length));
+    if cubes.is_none_or(|cubes| cubes > MAX_CUBES) {
+        return Err(Error::at(
+            line,
+            line,
+            ErrorKind::TooManyCubes(line.to_string()),
+        ));
+    }
+    Ok(brick)
+}
+
+//Which bricks rest on which, once they have all settled.
+//Bricks are referred to by their index in the settled bricks.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SupportGraph {
+    //the bricks resting directly on top of each brick
+    pub supports: Vec<Vec<usize>>,
+    //the bricks each brick rests directly on top of, empty if it is on the ground
+    pub supported_by: Vec<Vec<usize>>,
+}
+
+impl SupportGraph {
+    pub fn len(&self) -> usize {
+        self.supports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.supports.is_empty()
+    }
+
+    //the bricks that every brick above them could do without
+    pub fn safe_to_disintegrate(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len()).filter(|&brick| {
+            self.supports[brick]
+                .iter()
+                .all(|&above| self.supported_by[above].len() > 1)
+        })
+    }
+
+    //how many other bricks would fall if the given one was disintegrated
+    pub fn count_falling(&self, brick: usize) -> usize {
+        //the number of bricks under each brick that have fallen
+        let mut fallen_under = HashMap::<usize, usize>::new();
+        let mut falling = VecDeque::from([brick]);
+        let mut count = 0;
+        while let Some(brick) = falling.pop_front() {
+            for &above in &self.supports[brick] {
+                let fallen = fallen_under.entry(above).or_default();
+                *fallen += 1;
+                if *fallen == self.supported_by[above].len() {
+                    falling.push_back(above);
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+//Drops every brick as far as it will go, lowest first.
+//Returns the settled bricks, sorted by how high they started, and what supports what.
+pub fn settle(bricks: &[Brick]) -> Result<(Vec<Brick>, SupportGraph)> {
+    //the highest brick at each (x, y), along with the height of its top
+    let mut tops = HashMap::<(u32, u32), (u32, usize)>::new();
+    let mut settled = Vec::with_capacity(bricks.len());
+    let mut graph = SupportGraph {
+        supports: vec![Vec::new(); bricks.len()],
+        supported_by: vec![Vec::new(); bricks.len()],
+    };
+
+    for (i, brick) in bricks
+        .iter()
+        .sorted_by_key(|brick| brick.bottom())
+        .enumerate()
+    {
+        let below = brick
+            .footprint()
+            .filter_map(|position| tops.get(&position))
+            .copied()
+            .collect_vec();
+        let floor = below.iter().map(|&(top, _)| top).max().unwrap_or(0);
+        let supporters = below
+            .into_iter()
+            .filter(|&(top, _)| top == floor)
+            .map(|(_, brick)| brick)
+            .unique()
+            .collect_vec();
+        for &supporter in &supporters {
+            graph.supports[supporter].push(i);
+        }
+        graph.supported_by[i] = supporters;
+
+        let brick = floor
+            .checked_add(1)
+            .and_then(|bottom| brick.moved_to(bottom))
+            .context("Bricks stack too high")?;
+        for position in brick.footprint() {
+            tops.insert(position, (brick.end[2], i));
+        }
+        settled.push(brick);
+    }
+    Ok((settled, graph))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day22::solve_part1(EXAMPLE)?, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day22::solve_part2(EXAMPLE)?, 7);
+        Ok(())
+    }
+
+    #[test]
+    fn example_graph() -> Result<()> {
+        let (settled, graph) = settle(&Day22::parse(EXAMPLE)?)?;
+        assert_eq!(graph.supports[0], [1, 2]);
+        assert_eq!(graph.supported_by[3], [1, 2]);
+        assert_eq!(graph.supported_by[6], [5]);
+        assert!(graph.supports[6].is_empty());
+        assert_eq!(settled[6].start, [1, 1, 5]);
+        assert_eq!(settled[6].end, [1, 1, 6]);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_bricks() {
+        let error = Day22::parse("1,0,1~1,2,1\n0,0,2~2,x,2").err();
+        let expected = Error::new(
+            Location::new(2, 9),
+            ErrorKind::InvalidCoordinate("x".into()),
+        );
+        assert_eq!(error, Some(expected));
+
+        let error = Day22::parse("1,0~1,2,1").err().map(|e| e.kind);
+        assert_eq!(error, Some(ErrorKind::InvalidBrick("1,0~1,2,1".into())));
+
+        let error = Day22::parse("0,0,0~0,0,4294967295").err().map(|e| e.kind);
+        let expected = ErrorKind::TooManyCubes("0,0,0~0,0,4294967295".into());
+        assert_eq!(error, Some(expected));
+        let error = Day22::parse("0,0,1~4000000000,4000000000,1")
+            .err()
+            .map(|e| e.to_string());
+        assert_eq!(
+            error.as_deref(),
+            Some("Brick '0,0,1~4000000000,4000000000,1' has more than 1000 cubes at line 1, column 1")
+        );
+    }
+}
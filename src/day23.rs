@@ -0,0 +1,260 @@
+use crate::{
+    error::{self, Location},
+    geometry::Direction,
+    grid::{self, Grid, Position},
+    Solution,
+};
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use std::collections::HashMap;
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u8 = 23;
+
+    type Input<'a> = Trails;
+    type Error = Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        parse_trails(input)
+    }
+
+    fn part1(trails: &Self::Input<'_>) -> Result<usize> {
+        longest_hike(&compress(trails, true))
+    }
+
+    fn part2(trails: &Self::Input<'_>) -> Result<usize> {
+        longest_hike(&compress(trails, false))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error(transparent)]
+    Grid(grid::ErrorKind),
+    #[error("Expected at least one row and column")]
+    Empty,
+    #[error("Expected a path in the top row")]
+    NoStart,
+    #[error("Expected a path in the bottom row")]
+    NoEnd,
+}
+
+pub type Error = error::Error<ErrorKind>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tile {
+    Path,
+    Forest,
+    //can only be left in its direction, if slopes are slippery
+    Slope(Direction),
+}
+
+pub struct Trails {
+    pub grid: Grid<Tile>,
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Trails {
+    //the positions that can be stepped to from the given one, along with the direction of the step
+    fn steps(
+        &self,
+        position: Position,
+        slippery: bool,
+    ) -> impl Iterator<Item = (Position, Direction)> + '_ {
+        Direction::iter()
+            .filter(move |&direction| match self.grid[position] {
+                Tile::Slope(slope) if slippery => slope == direction,
+                _ => true,
+            })
+            .filter_map(move |direction| {
+                self.grid
+                    .neighbor(position, direction)
+                    .filter(|&next| self.grid[next] != Tile::Forest)
+                    .map(|next| (next, direction))
+            })
+    }
+
+    //a position where the trail splits, or where it starts or ends
+    fn is_junction(&self, position: Position) -> bool {
+        position == self.start || position == self.end || self.steps(position, false).count() > 2
+    }
+}
+
+pub fn parse_trails(input: &str) -> Result<Trails, Error> {
+    let grid = Grid::parse(input, |c| match c {
+        '.' => Some(Tile::Path),
+        '#' => Some(Tile::Forest),
+        _ => Direction::from_arrow(c).map(Tile::Slope),
+    })
+    .map_err(|e| e.map(ErrorKind::Grid))?;
+    if grid.row_count() == 0 || grid.col_count() == 0 {
+        return Err(Error::new(Location::new(1, 1), ErrorKind::Empty));
+    }
+
+    let opening = |row: usize| grid.row(row).iter().position(|&tile| tile == Tile::Path);
+    let start = opening(0).ok_or_else(|| Error::new(Location::new(1, 1), ErrorKind::NoStart))?;
+    let last = grid.row_count() - 1;
+    let end =
+        opening(last).ok_or_else(|| Error::new(Location::new(last + 1, 1), ErrorKind::NoEnd))?;
+    Ok(Trails {
+        start: (0, start),
+        end: (last, end),
+        grid,
+    })
+}
+
+//The trails as a graph of the places they split, with an edge for every trail between two of them
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct JunctionGraph {
+    pub junctions: Vec<Position>,
+    //(junction, length) of every trail leading away from each junction
+    pub edges: Vec<Vec<(usize, usize)>>,
+    pub start: usize,
+    pub end: usize,
+}
+
+//Finds every trail between two junctions by following it until the next junction.
+//With slippery slopes, a trail can only be followed in the directions its slopes allow.
+pub fn compress(trails: &Trails, slippery: bool) -> JunctionGraph {
+    let junctions = trails
+        .grid
+        .positions()
+        .filter(|&position| trails.grid[position] != Tile::Forest && trails.is_junction(position))
+        .collect_vec();
+    let indices: HashMap<_, _> = junctions.iter().enumerate().map(|(i, &p)| (p, i)).collect();
+
+    let edges = junctions
+        .iter()
+        .map(|&junction| {
+            trails
+                .steps(junction, slippery)
+                .filter_map(|(mut position, mut direction)| {
+                    let mut length = 1;
+                    while !indices.contains_key(&position) {
+                        //there is only one way forward that is not straight back
+                        (position, direction) = trails
+                            .steps(position, slippery)
+                            .find(|&(_, next)| next != direction.reverse())?;
+                        length += 1;
+                    }
+                    Some((indices[&position], length))
+                })
+                .collect_vec()
+        })
+        .collect_vec();
+
+    JunctionGraph {
+        start: indices[&trails.start],
+        end: indices[&trails.end],
+        junctions,
+        edges,
+    }
+}
+
+//The length of the longest hike from start to end that never visits a junction twice.
+//Tries every hike, keeping track of the junctions visited so far in a bitmask.
+pub fn longest_hike(graph: &JunctionGraph) -> Result<usize> {
+    if graph.junctions.len() > 64 {
+        bail!("Too many junctions, expected at most 64");
+    }
+
+    //Once at the only junction leading to the end, the hike has to go to the end.
+    //Going anywhere else would make the end unreachable.
+    let into_end = graph
+        .edges
+        .iter()
+        .enumerate()
+        .flat_map(|(from, edges)| {
+            edges
+                .iter()
+                .filter(|&&(to, _)| to == graph.end)
+                .map(move |&(_, length)| (from, length))
+        })
+        .exactly_one()
+        .ok();
+
+    fn search(
+        graph: &JunctionGraph,
+        into_end: Option<(usize, usize)>,
+        junction: usize,
+        visited: u64,
+        length: usize,
+    ) -> Option<usize> {
+        if junction == graph.end {
+            return Some(length);
+        }
+        if let Some((last, to_end)) = into_end {
+            if junction == last {
+                return Some(length + to_end);
+            }
+        }
+        graph.edges[junction]
+            .iter()
+            .filter(|&&(next, _)| visited & (1 << next) == 0)
+            .filter_map(|&(next, step)| {
+                search(graph, into_end, next, visited | (1 << next), length + step)
+            })
+            .max()
+    }
+
+    search(graph, into_end, graph.start, 1 << graph.start, 0)
+        .context("No hike leads from the start to the end")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day23::solve_part1(EXAMPLE)?, 94);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day23::solve_part2(EXAMPLE)?, 154);
+        Ok(())
+    }
+
+    #[test]
+    fn example_graph() -> Result<()> {
+        let trails = Day23::parse(EXAMPLE)?;
+        let graph = compress(&trails, false);
+        //the start, the end and 7 places where the trail splits
+        assert_eq!(graph.junctions.len(), 9);
+        let first = graph.junctions.iter().position(|&p| p == (5, 3)).unwrap();
+        assert_eq!(graph.edges[graph.start], [(first, 15)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+use crate::{
+    error::{self, Location},
+    Solution,
+};
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Signed, ToPrimitive, Zero};
+use std::ops::RangeInclusive;
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u8 = 24;
+
+    type Input<'a> = Vec<Hailstone>;
+    type Error = Error;
+    type Answer1 = usize;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                parse_hailstone(line).map_err(|e| e.offset_by(Location::new(i + 1, 1)))
+            })
+            .collect()
+    }
+
+    fn part1(hail: &Self::Input<'_>) -> Result<usize> {
+        Ok(count_crossings(hail, 200000000000000..=400000000000000))
+    }
+
+    fn part2(hail: &Self::Input<'_>) -> Result<i64> {
+        let rock = find_throw(hail)?;
+        rock.position
+            .iter()
+            .try_fold(0_i64, |sum, &coordinate| sum.checked_add(coordinate))
+            .context("Rock position too large to add up")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected a hailstone like '19, 13, 30 @ -2, 1, -2', found '{0}'")]
+    InvalidHailstone(String),
+    #[error("Invalid number '{0}'")]
+    InvalidNumber(String),
+}
+
+pub type Error = error::Error<ErrorKind>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Hailstone {
+    pub position: [i64; 3],
+    pub velocity: [i64; 3],
+}
+
+impl Hailstone {
+    fn position(&self, axis: usize) -> BigRational {
+        BigRational::from_integer(self.position[axis].into())
+    }
+
+    fn velocity(&self, axis: usize) -> BigRational {
+        BigRational::from_integer(self.velocity[axis].into())
+    }
+}
+
+//locations in errors are relative to the line
+pub fn parse_hailstone(line: &str) -> Result<Hailstone, Error> {
+    let invalid = || Error::at(line, line, ErrorKind::InvalidHailstone(line.to_string()));
+    let parse_vector = |vector: &str| -> Result<[i64; 3], Error> {
+        vector
+            .split(',')
+            .map(|num| {
+                let num = num.trim();
+                num.parse()
+                    .map_err(|_| Error::at(line, num, ErrorKind::InvalidNumber(num.to_string())))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .map_err(|_| invalid())
+    };
+
+    let (position, velocity) = line.split_once('@').ok_or_else(invalid)?;
+    Ok(Hailstone {
+        position: parse_vector(position)?,
+        velocity: parse_vector(velocity)?,
+    })
+}
+
+//where the paths of two hailstones cross, ignoring z, and when each of them gets there
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Crossing {
+    pub x: BigRational,
+    pub y: BigRational,
+    pub times: (BigRational, BigRational),
+}
+
+//None if the paths are parallel, including if they are the same path
+pub fn crossing(a: &Hailstone, b: &Hailstone) -> Option<Crossing> {
+    //solving a.position + t * a.velocity = b.position + s * b.velocity with Cramer's rule
+    let determinant = a.velocity(0) * b.velocity(1) - a.velocity(1) * b.velocity(0);
+    if determinant.is_zero() {
+        return None;
+    }
+    let dx = b.position(0) - a.position(0);
+    let dy = b.position(1) - a.position(1);
+    let t = (&dx * b.velocity(1) - &dy * b.velocity(0)) / &determinant;
+    let s = (dx * a.velocity(1) - dy * a.velocity(0)) / determinant;
+    Some(Crossing {
+        x: a.position(0) + &t * a.velocity(0),
+        y: a.position(1) + &t * a.velocity(1),
+        times: (t, s),
+    })
+}
+
+//the pairs of hailstones whose paths cross inside the area, ignoring z, without either going back in time
+pub fn count_crossings(hail: &[Hailstone], area: RangeInclusive<i64>) -> usize {
+    hail.iter()
+        .tuple_combinations()
+        .filter(|&(a, b)| {
+            crosses_inside_quickly(a, b, &area).unwrap_or_else(|| crosses_inside(a, b, &area))
+        })
+        .count()
+}
+
+fn crosses_inside(a: &Hailstone, b: &Hailstone, area: &RangeInclusive<i64>) -> bool {
+    let min = BigRational::from_integer((*area.start()).into());
+    let max = BigRational::from_integer((*area.end()).into());
+    let inside = |coordinate: &BigRational| &min <= coordinate && coordinate <= &max;
+    crossing(a, b).is_some_and(|crossing| {
+        !crossing.times.0.is_negative()
+            && !crossing.times.1.is_negative()
+            && inside(&crossing.x)
+            && inside(&crossing.y)
+    })
+}
+
+//Like crosses_inside, but with i128 instead of fractions, which is plenty for real inputs.
+//None if anything overflows.
+fn crosses_inside_quickly(
+    a: &Hailstone,
+    b: &Hailstone,
+    area: &RangeInclusive<i64>,
+) -> Option<bool> {
+    let ([ax, ay, _], [avx, avy, _]) = (a.position.map(i128::from), a.velocity.map(i128::from));
+    let ([bx, by, _], [bvx, bvy, _]) = (b.position.map(i128::from), b.velocity.map(i128::from));
+    let (min, max) = (i128::from(*area.start()), i128::from(*area.end()));
+
+    //the times are t / determinant and s / determinant, as in crossing
+    let mut determinant = avx.checked_mul(bvy)?.checked_sub(avy.checked_mul(bvx)?)?;
+    if determinant == 0 {
+        return Some(false);
+    }
+    let (dx, dy) = (bx.checked_sub(ax)?, by.checked_sub(ay)?);
+    let mut t = dx.checked_mul(bvy)?.checked_sub(dy.checked_mul(bvx)?)?;
+    let mut s = dx.checked_mul(avy)?.checked_sub(dy.checked_mul(avx)?)?;
+    if determinant < 0 {
+        (determinant, t, s) = (-determinant, -t, -s);
+    }
+    if t < 0 || s < 0 {
+        return Some(false);
+    }
+
+    //compares position + t / determinant * velocity with the area, times determinant
+    let inside = |position: i128, velocity: i128| -> Option<bool> {
+        let scaled = position
+            .checked_mul(determinant)?
+            .checked_add(t.checked_mul(velocity)?)?;
+        Some(min.checked_mul(determinant)? <= scaled && scaled <= max.checked_mul(determinant)?)
+    };
+    Some(inside(ax, avx)? && inside(ay, avy)?)
+}
+
+fn vector(coordinate: impl FnMut(usize) -> BigRational) -> [BigRational; 3] {
+    [0, 1, 2].map(coordinate)
+}
+
+fn cross(a: &[BigRational; 3], b: &[BigRational; 3]) -> [BigRational; 3] {
+    [
+        &a[1] * &b[2] - &a[2] * &b[1],
+        &a[2] * &b[0] - &a[0] * &b[2],
+        &a[0] * &b[1] - &a[1] * &b[0],
+    ]
+}
+
+//The throw that hits every hailstone.
+//The rock at p with velocity v hits hailstone i when (p - p_i) x (v - v_i) = 0. The p x v in that is
+//the same for every hailstone, so subtracting the equations of two hailstones leaves 3 linear equations.
+//Two pairs of hailstones are enough to solve for the 6 unknowns, the rest only have to agree.
+pub fn find_throw(hail: &[Hailstone]) -> Result<Hailstone> {
+    let positions = hail.iter().map(|h| vector(|i| h.position(i))).collect_vec();
+    let velocities = hail.iter().map(|h| vector(|i| h.velocity(i))).collect_vec();
+
+    //the unit vector along an axis, to find the coefficients of the cross products
+    let unit = |axis: usize| vector(|i| BigRational::from_integer((i == axis).into()));
+
+    //for the hailstones 0 and j, p x (v_j - v_0) + (p_j - p_0) x v = p_j x v_j - p_0 x v_0
+    let equations = (1..hail.len())
+        .flat_map(|j| {
+            let d = vector(|axis| &velocities[j][axis] - &velocities[0][axis]);
+            let e = vector(|axis| &positions[j][axis] - &positions[0][axis]);
+            let rhs = vector(|axis| {
+                &cross(&positions[j], &velocities[j])[axis]
+                    - &cross(&positions[0], &velocities[0])[axis]
+            });
+            (0..3)
+                .map(|row| {
+                    let p = (0..3).map(|axis| cross(&unit(axis), &d)[row].clone());
+                    let v = (0..3).map(|axis| cross(&e, &unit(axis))[row].clone());
+                    p.chain(v).chain([rhs[row].clone()]).collect_vec()
+                })
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let solution = solve(equations).context("The hailstones do not pin down a single throw")?;
+    let whole = solution
+        .iter()
+        .map(|value| {
+            value
+                .is_integer()
+                .then(|| value.to_integer())
+                .and_then(|value: BigInt| value.to_i64())
+        })
+        .collect::<Option<Vec<_>>>()
+        .context("The throw does not start at a whole position with a whole velocity")?;
+    let rock = Hailstone {
+        position: [whole[0], whole[1], whole[2]],
+        velocity: [whole[3], whole[4], whole[5]],
+    };
+
+    //the equations only used differences, so check that every hailstone really gets hit
+    let rock_position = vector(|axis| rock.position(axis));
+    let rock_velocity = vector(|axis| rock.velocity(axis));
+    for (position, velocity) in positions.iter().zip(&velocities) {
+        let offset = vector(|axis| &rock_position[axis] - &position[axis]);
+        let relative = vector(|axis| &rock_velocity[axis] - &velocity[axis]);
+        if cross(&offset, &relative)
+            .iter()
+            .any(|value| !value.is_zero())
+        {
+            bail!("No single throw hits every hailstone");
+        }
+    }
+    Ok(rock)
+}
+
+//Gaussian elimination on rows of coefficients followed by the right hand side.
+//There can be more equations than unknowns, as long as they agree. None unless there is exactly one solution.
+fn solve(mut rows: Vec<Vec<BigRational>>) -> Option<Vec<BigRational>> {
+    let unknowns = rows.first()?.len() - 1;
+    for column in 0..unknowns {
+        let pivot = (column..rows.len()).find(|&row| !rows[row][column].is_zero())?;
+        rows.swap(column, pivot);
+        let pivot_row = rows[column].clone();
+        for (i, row) in rows.iter_mut().enumerate() {
+            if i == column || row[column].is_zero() {
+                continue;
+            }
+            let factor = &row[column] / &pivot_row[column];
+            for (value, pivot_value) in row.iter_mut().zip(&pivot_row) {
+                *value -= &factor * pivot_value;
+            }
+        }
+    }
+    if rows[unknowns..].iter().any(|row| !row[unknowns].is_zero()) {
+        return None;
+    }
+    Some(
+        rows.iter()
+            .take(unknowns)
+            .enumerate()
+            .map(|(i, row)| &row[unknowns] / &row[i])
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        let hail = Day24::parse(EXAMPLE)?;
+        assert_eq!(count_crossings(&hail, 7..=27), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day24::solve_part2(EXAMPLE)?, 47);
+        let rock = find_throw(&Day24::parse(EXAMPLE)?)?;
+        assert_eq!(rock.position, [24, 13, 10]);
+        assert_eq!(rock.velocity, [-3, 1, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn exact_crossings() {
+        //far enough apart that f64 would round the crossing
+        let a = parse_hailstone("300000000000001, 0, 0 @ 0, 1, 0").unwrap();
+        let b = parse_hailstone("0, 300000000000002, 0 @ 3, -1, 0").unwrap();
+        let crossing = crossing(&a, &b).unwrap();
+        assert_eq!(
+            crossing.x,
+            BigRational::from_integer(300000000000001_i64.into())
+        );
+        assert_eq!(
+            crossing.y,
+            BigRational::new(600000000000005_i64.into(), 3.into())
+        );
+    }
+
+    #[test]
+    fn huge_coordinates() -> Result<()> {
+        //too big for i128, so this has to fall back to fractions
+        //the paths cross at (max, 0)
+        let hail = Day24::parse(&format!(
+            "{min}, 0, 0 @ {max}, 0, 0\n{max}, {min}, 0 @ 0, {max}, 0",
+            min = i64::MIN,
+            max = i64::MAX
+        ))?;
+        assert!(crosses_inside_quickly(&hail[0], &hail[1], &(i64::MIN..=i64::MAX)).is_none());
+        assert_eq!(count_crossings(&hail, i64::MIN..=i64::MAX), 1);
+        Ok(())
+    }
+}
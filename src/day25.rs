@@ -0,0 +1,256 @@
+use crate::{
+    error::{self, Location},
+    Solution,
+};
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use std::{
+    collections::{HashMap, VecDeque},
+    iter,
+};
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u8 = 25;
+    const HAS_PART2: bool = false;
+
+    type Input<'a> = Wiring<'a>;
+    type Error = Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        parse_wiring(input)
+    }
+
+    fn part1(wiring: &Self::Input<'_>) -> Result<usize> {
+        let cut = three_wire_cut(wiring)?;
+        Ok(cut.groups[0].len() * cut.groups[1].len())
+    }
+
+    fn part2(_: &Self::Input<'_>) -> Result<usize> {
+        bail!("Day 25 has no part 2")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected ':' after the component name")]
+    MissingColon,
+    #[error("Invalid component name '{0}'")]
+    InvalidName(String),
+}
+
+pub type Error = error::Error<ErrorKind>;
+
+//Components are referred to by their index
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Wiring<'a> {
+    pub names: Vec<&'a str>,
+    //the components each component is wired to, without duplicates
+    pub connections: Vec<Vec<usize>>,
+}
+
+pub fn parse_wiring<'a>(input: &'a str) -> Result<Wiring<'a>, Error> {
+    let name = |name: &'a str| -> Result<&'a str, Error> {
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric()) {
+            Ok(name)
+        } else {
+            Err(Error::at(
+                input,
+                name,
+                ErrorKind::InvalidName(name.to_string()),
+            ))
+        }
+    };
+
+    let mut indices = HashMap::new();
+    let mut names = Vec::new();
+    let mut wires = Vec::new();
+    for line in input.lines().filter(|line| !line.trim().is_empty()) {
+        let (component, others) = line.split_once(':').ok_or_else(|| {
+            Error::new(
+                Location::of(input, &line[line.len()..]),
+                ErrorKind::MissingColon,
+            )
+        })?;
+        let component = name(component.trim())?;
+        let others = others
+            .split_whitespace()
+            .map(name)
+            .collect::<Result<Vec<_>, _>>()?;
+        for &name in iter::once(&component).chain(&others) {
+            indices.entry(name).or_insert_with(|| {
+                names.push(name);
+                names.len() - 1
+            });
+        }
+        wires.extend(
+            others
+                .iter()
+                .map(|other| (indices[component], indices[other])),
+        );
+    }
+
+    let mut connections = vec![Vec::new(); names.len()];
+    for (a, b) in wires {
+        if a != b {
+            connections[a].push(b);
+            connections[b].push(a);
+        }
+    }
+    for connected in &mut connections {
+        connected.sort_unstable();
+        connected.dedup();
+    }
+    Ok(Wiring { names, connections })
+}
+
+//A split of the components into two groups, and the wires between them
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Cut<'a> {
+    pub groups: [Vec<&'a str>; 2],
+    //each wire is (a, b) with a < b, sorted
+    pub wires: Vec<(&'a str, &'a str)>,
+}
+
+//The cut of exactly 3 wires the puzzle is about.
+//The runner only prints the product of the group sizes, this is where the wires themselves come from.
+pub fn three_wire_cut<'a>(wiring: &Wiring<'a>) -> Result<Cut<'a>> {
+    let cut = minimum_cut(wiring).context("Expected at least two components")?;
+    if cut.wires.len() != 3 {
+        bail!(
+            "Expected the groups to be joined by 3 wires, but {} can be cut",
+            cut.wires.len()
+        );
+    }
+    Ok(cut)
+}
+
+//The cut with the fewest wires.
+//Every cut separates the first component from some other one, so this finds the minimum cut between
+//the first component and each of the others with max flow, keeping the smallest.
+pub fn minimum_cut<'a>(wiring: &Wiring<'a>) -> Option<Cut<'a>> {
+    let mut best: Option<Cut> = None;
+    for sink in 1..wiring.names.len() {
+        let limit = best.as_ref().map(|cut| cut.wires.len());
+        if let Some(cut) = minimum_cut_between(wiring, 0, sink, limit) {
+            best = Some(cut);
+        }
+    }
+    best
+}
+
+//Edmonds-Karp with every wire carrying 1 unit of flow in either direction.
+//Gives up as soon as the cut would be at least limit wires.
+fn minimum_cut_between<'a>(
+    wiring: &Wiring<'a>,
+    source: usize,
+    sink: usize,
+    limit: Option<usize>,
+) -> Option<Cut<'a>> {
+    //flow[(a, b)] is the flow from a to b, and always -flow[(b, a)]
+    let mut flow = HashMap::<(usize, usize), i32>::new();
+    let mut paths = 0;
+
+    loop {
+        //breadth first search through wires with room for more flow
+        let mut came_from = vec![None; wiring.names.len()];
+        came_from[source] = Some(source);
+        let mut queue = VecDeque::from([source]);
+        while let Some(component) = queue.pop_front() {
+            for &next in &wiring.connections[component] {
+                if came_from[next].is_none()
+                    && flow.get(&(component, next)).copied().unwrap_or(0) < 1
+                {
+                    came_from[next] = Some(component);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if came_from[sink].is_none() {
+            //everything still reachable is on the source's side of the cut
+            let (side, other): (Vec<_>, Vec<_>) =
+                (0..wiring.names.len()).partition(|&component| came_from[component].is_some());
+            let wires = side
+                .iter()
+                .flat_map(|&a| {
+                    wiring.connections[a]
+                        .iter()
+                        .filter(|&&b| came_from[b].is_none())
+                        .map(move |&b| {
+                            let (a, b) = (wiring.names[a], wiring.names[b]);
+                            (a.min(b), a.max(b))
+                        })
+                })
+                .sorted()
+                .collect_vec();
+            let names = |group: Vec<usize>| group.into_iter().map(|i| wiring.names[i]).collect();
+            return Some(Cut {
+                groups: [names(side), names(other)],
+                wires,
+            });
+        }
+
+        paths += 1;
+        if limit.is_some_and(|limit| paths >= limit) {
+            return None;
+        }
+        let mut component = sink;
+        while component != source {
+            let previous = came_from[component]?;
+            *flow.entry((previous, component)).or_default() += 1;
+            *flow.entry((component, previous)).or_default() -= 1;
+            component = previous;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day25::solve_part1(EXAMPLE)?, 54);
+        Ok(())
+    }
+
+    #[test]
+    fn example_cut() -> Result<()> {
+        let cut = three_wire_cut(&Day25::parse(EXAMPLE)?)?;
+        assert_eq!(cut.wires, [("bvb", "cmg"), ("hfx", "pzl"), ("jqt", "nvd")]);
+        Ok(())
+    }
+
+    #[test]
+    fn no_part2() {
+        let error = Day25::solve_part2(EXAMPLE).err().map(|e| e.to_string());
+        assert_eq!(error.as_deref(), Some("Day 25 has no part 2"));
+    }
+
+    #[test]
+    fn disconnected() -> Result<()> {
+        let wiring = Day25::parse("a: b\nc: d")?;
+        assert_eq!(minimum_cut(&wiring).map(|cut| cut.wires.len()), Some(0));
+        assert!(three_wire_cut(&wiring).is_err());
+        Ok(())
+    }
+}
@@ -1,104 +1,121 @@
+use crate::{
+    grid::{self, Grid, Position},
+    Solution,
+};
 use anyhow::Result;
 use itertools::Itertools;
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day3.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input<'a> = Grid<char>;
+    type Error = grid::Error;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, grid::Error> {
+        Grid::parse(input, Some)
+    }
+
+    fn part1(grid: &Self::Input<'_>) -> Result<u32> {
+        sum_part_numbers(grid)
+    }
+
+    fn part2(grid: &Self::Input<'_>) -> Result<u32> {
+        sum_gear_ratios(grid)
+    }
+}
+
+fn is_symbol(c: char) -> bool {
+    !c.is_ascii_digit() && c != '.'
+}
+
+//the position where the number covering this position starts, if there is one
+fn number_start(grid: &Grid<char>, (row, col): Position) -> Option<Position> {
+    if !grid[(row, col)].is_ascii_digit() {
+        return None;
+    }
+    let start = grid.row(row)[..col]
+        .iter()
+        .rev()
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    Some((row, col - start))
 }
 
-fn part1(input: &str) -> Result<u32> {
-    let grid: Vec<Vec<_>> = input.lines().map(|line| line.chars().collect()).collect();
-    let col_count = grid[0].len();
-    let row_count = grid.len();
-    (0..row_count)
-        .cartesian_product(0..col_count)
-        .filter_map(|(row_index, col_index)| {
-            let num_start = grid[row_index][col_index];
-            if !num_start.is_ascii_digit() {
-                return None;
-            }
-
-            let predecessor = col_index
-                .checked_sub(1)
-                .and_then(|predecessor_index| grid[row_index].get(predecessor_index));
-            if predecessor.is_some_and(|c| c.is_ascii_digit()) {
-                return None;
-            }
-
-            let num = {
-                let row = &grid[row_index];
-                (col_index..)
-                    .map_while(|offset| row.get(offset).filter(|c| c.is_ascii_digit()))
-                    .collect::<String>()
-            };
-
-            let num_length = num.chars().count();
-            (-1..=1)
-                .cartesian_product(-1..=num_length as isize)
-                .filter_map(|(i, j)| {
-                    grid.get(row_index.checked_add_signed(i)?)?
-                        .get(col_index.checked_add_signed(j)?)
-                })
-                .any(|c| !c.is_ascii_digit() && *c != '.')
-                .then(|| num.parse::<u32>().map_err(Into::into))
+//the digits of the number starting at this position
+fn number_digits(grid: &Grid<char>, (row, col): Position) -> impl Iterator<Item = Position> + '_ {
+    grid.row(row)[col..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .enumerate()
+        .map(move |(offset, _)| (row, col + offset))
+}
+
+fn parse_number(grid: &Grid<char>, start: Position) -> Result<u32> {
+    let num = number_digits(grid, start)
+        .map(|position| grid[position])
+        .collect::<String>();
+    num.parse().map_err(Into::into)
+}
+
+//sums every number adjacent to a symbol
+pub fn sum_part_numbers(grid: &Grid<char>) -> Result<u32> {
+    grid.positions()
+        .filter(|&position| number_start(grid, position) == Some(position))
+        .filter(|&start| {
+            number_digits(grid, start)
+                .flat_map(|digit| grid.neighbors8(digit))
+                .any(|neighbor| is_symbol(grid[neighbor]))
         })
+        .map(|start| parse_number(grid, start))
         .sum()
 }
 
-fn part2(input: &str) -> Result<u32> {
-    let grid: Vec<Vec<_>> = input.lines().map(|line| line.chars().collect()).collect();
-    let col_count = grid[0].len();
-    let row_count = grid.len();
-    (0..row_count)
-        .cartesian_product(0..col_count)
-        .filter_map(|(row_index, col_index)| {
-            let gear = grid[row_index][col_index];
-            if gear != '*' {
-                return None;
-            }
-
-            let neighboring_nums = (row_index.saturating_sub(1)..=row_index + 1)
-                .cartesian_product(0..col_count)
-                .filter_map(|(i, j)| {
-                    let num_start = grid.get(i)?.get(j)?;
-
-                    if !num_start.is_ascii_digit() {
-                        return None;
-                    }
-
-                    let predecessor = grid.get(i).and_then(|row| row.get(j.checked_sub(1)?));
-                    if predecessor.is_some_and(|c| c.is_ascii_digit()) {
-                        return None;
-                    }
-
-                    let num = {
-                        let row = &grid.get(i)?;
-                        (j..)
-                            .map_while(|offset| row.get(offset).filter(|c| c.is_ascii_digit()))
-                            .collect::<String>()
-                    };
-
-                    if j > col_index + 1 {
-                        return None;
-                    }
-
-                    let num_length = num.chars().count();
-                    if j + num_length < col_index {
-                        return None;
-                    }
-
-                    Some(num)
-                });
-
-            let (first, second) = neighboring_nums.collect_tuple()?;
+//sums the products of the two numbers adjacent to each gear
+pub fn sum_gear_ratios(grid: &Grid<char>) -> Result<u32> {
+    grid.iter()
+        .filter(|&(_, &c)| c == '*')
+        .filter_map(|(gear, _)| {
+            let (first, second) = grid
+                .neighbors8(gear)
+                .filter_map(|neighbor| number_start(grid, neighbor))
+                .unique()
+                .collect_tuple()?;
             Some(
-                first
-                    .parse::<u32>()
-                    .and_then(|first| Ok(first * second.parse::<u32>()?))
-                    .map_err(Into::into),
+                parse_number(grid, first).and_then(|first| Ok(first * parse_number(grid, second)?)),
             )
         })
         .sum()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day3::solve_part1(EXAMPLE)?, 4361);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day3::solve_part2(EXAMPLE)?, 467835);
+        Ok(())
+    }
+}
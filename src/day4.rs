@@ -1,75 +1,186 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashSet, VecDeque},
+    io::BufRead,
+};
 
+use crate::{
+    error::{self, Location},
+    stream::{self, Streaming},
+    Solution,
+};
 use anyhow::{Context, Result};
-use itertools::Itertools;
+use itertools::{process_results, Itertools};
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day4.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input<'a> = Vec<Card>;
+    type Error = Error;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_card(line).map_err(|e| e.offset_by(Location::new(i + 1, 1))))
+            .collect()
+    }
+
+    fn part1(cards: &Self::Input<'_>) -> Result<u64> {
+        total_points(cards.iter().map(Card::points)).context("Too many points to count")
+    }
+
+    fn part2(cards: &Self::Input<'_>) -> Result<u64> {
+        count_cards(cards.iter().map(Card::matches)).context("Too many cards to count")
+    }
+}
+
+impl Streaming for Day4 {
+    fn stream_part1(input: impl BufRead) -> Result<u64> {
+        let points = stream::parse_lines(input, parse_card).map_ok(|card| card.points());
+        process_results(points, |points| total_points(points))?.context("Too many points to count")
+    }
+
+    fn stream_part2(input: impl BufRead) -> Result<u64> {
+        let matches = stream::parse_lines(input, parse_card).map_ok(|card| card.matches());
+        process_results(matches, |matches| count_cards(matches))?.context("Too many cards to count")
+    }
+}
+
+//None if any card's points, or their sum, do not fit
+fn total_points(mut points: impl Iterator<Item = Option<u64>>) -> Option<u64> {
+    points.try_fold(0_u64, |sum, points| sum.checked_add(points?))
+}
+
+//Counts every card, including the copies won from the cards before it.
+//Only the copies won of upcoming cards need to be remembered, so this works on any number of cards.
+//None if there are too many cards to count.
+pub fn count_cards(matches: impl Iterator<Item = usize>) -> Option<u64> {
+    //upcoming[i] is the number of copies won of the card i places ahead
+    let mut upcoming = VecDeque::new();
+    let mut total = 0_u64;
+    for matches in matches {
+        let count = upcoming.pop_front().unwrap_or(0_u64).checked_add(1)?;
+        if upcoming.len() < matches {
+            upcoming.resize(matches, 0);
+        }
+        for copies in upcoming.iter_mut().take(matches) {
+            *copies = copies.checked_add(count)?;
+        }
+        total = total.checked_add(count)?;
+    }
+    Some(total)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected ':' after the card id")]
+    MissingColon,
+    #[error("Expected '|' between the winning numbers and my numbers")]
+    MissingBar,
+    #[error("Invalid number '{0}'")]
+    InvalidNumber(String),
 }
 
-fn part1(input: &str) -> Result<u32> {
-    input
-        .lines()
-        .map(|line| {
-            let (winning_nums, my_nums) =
-                line.split_once('|').context("Error splitting on \'|\'")?;
-            let winning_nums = winning_nums
-                .split_once(':')
-                .context("Error splitting on \':\'")?
-                .1
-                .split_whitespace()
-                .map(|num| num.parse())
-                .collect::<Result<HashSet<u32>, _>>()?;
-
-            my_nums
-                .split_whitespace()
-                .map(|num| num.parse::<u32>())
-                .filter_ok(|num| winning_nums.contains(num))
-                .process_results(|iter| iter.count())
-                .map(|count| match count {
-                    0 => 0,
-                    _ => 2_u32.pow(count as u32 - 1),
-                })
-                .map_err(Into::into)
-        })
-        .sum()
+pub type Error = error::Error<ErrorKind>;
+
+pub struct Card {
+    pub winning_nums: HashSet<u32>,
+    pub my_nums: Vec<u32>,
 }
 
-fn part2(input: &str) -> Result<u32> {
-    let card_count = input.lines().count();
-    input
-        .lines()
-        .enumerate()
-        .map(|(i, line)| -> Result<_> {
-            let (winning_nums, my_nums) =
-                line.split_once('|').context("Error splitting on \'|\'")?;
-            let (_, winning_nums) = winning_nums
-                .split_once(':')
-                .context("Error splitting on \':\'")?;
-            let winning_nums = winning_nums
-                .split_whitespace()
-                .map(|num| num.parse())
-                .collect::<Result<HashSet<u32>, _>>()?;
-
-            my_nums
-                .split_whitespace()
-                .map(|num| num.parse::<u32>())
-                .filter_ok(|num| winning_nums.contains(num))
-                .process_results(|iter| iter.count())
-                .map(|matches| (i, matches))
-                .map_err(Into::into)
-        })
-        .fold_ok(vec![1; card_count], |mut counts, (card_index, matches)| {
-            let count = counts[card_index];
-            for i in (card_index + 1)..=(card_index + matches) {
-                if let Some(elem) = counts.get_mut(i) {
-                    *elem += count;
-                }
-            }
-            counts
-        })
-        .map(|map| map.into_iter().sum())
+impl Card {
+    //how many of my numbers are winning numbers
+    pub fn matches(&self) -> usize {
+        self.my_nums
+            .iter()
+            .filter(|num| self.winning_nums.contains(num))
+            .count()
+    }
+
+    pub fn points(&self) -> Option<u64> {
+        match self.matches() {
+            0 => Some(0),
+            count => 2_u64.checked_pow(count as u32 - 1),
+        }
+    }
+}
+
+//locations in errors are relative to the line
+pub fn parse_card(line: &str) -> Result<Card, Error> {
+    let missing = |kind| Error::new(Location::end_of(line), kind);
+    let parse_num = |num: &str| {
+        num.parse()
+            .map_err(|_| Error::at(line, num, ErrorKind::InvalidNumber(num.to_string())))
+    };
+
+    let (winning_nums, my_nums) = line
+        .split_once('|')
+        .ok_or_else(|| missing(ErrorKind::MissingBar))?;
+    let (_, winning_nums) = winning_nums
+        .split_once(':')
+        .ok_or_else(|| missing(ErrorKind::MissingColon))?;
+    let winning_nums = winning_nums
+        .split_whitespace()
+        .map(parse_num)
+        .collect::<Result<HashSet<u32>, _>>()?;
+    let my_nums = my_nums
+        .split_whitespace()
+        .map(parse_num)
+        .collect::<Result<Vec<u32>, _>>()?;
+    Ok(Card {
+        winning_nums,
+        my_nums,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day4::solve_part1(EXAMPLE)?, 13);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day4::solve_part2(EXAMPLE)?, 30);
+        Ok(())
+    }
+
+    #[test]
+    fn too_many_cards() {
+        //every card wins copies of the next 10, which doubles the count every card or so
+        let input = (1..=80)
+            .map(|id| format!("Card {}: 1 2 3 4 5 6 7 8 9 10 | 1 2 3 4 5 6 7 8 9 10", id))
+            .join("\n");
+        let error = Day4::solve_part2(&input).err().map(|e| e.to_string());
+        assert_eq!(error.as_deref(), Some("Too many cards to count"));
+
+        let input = (1..=3)
+            .map(|id| {
+                format!(
+                    "Card {}: {} | {}",
+                    id,
+                    (1..=64).join(" "),
+                    (1..=64).join(" ")
+                )
+            })
+            .join("\n");
+        let error = Day4::solve_part1(&input).err().map(|e| e.to_string());
+        assert_eq!(error.as_deref(), Some("Too many points to count"));
+    }
 }
@@ -1,89 +1,530 @@
-use anyhow::{Context, Result};
+use crate::{
+    error::{self, Location},
+    Solution,
+};
+use anyhow::{bail, Context, Result};
 use itertools::Itertools;
-use rayon::prelude::*;
+use std::{
+    fmt::{self, Display},
+    iter,
+    ops::Range,
+};
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day5.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input<'a> = Almanac;
+    type Error = Error;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        let seeds = parse_seeds(input)?;
+        let maps = parse_transforms(input)?;
+        Ok(Almanac { seeds, maps })
+    }
+
+    fn part1(almanac: &Self::Input<'_>) -> Result<u64> {
+        let map = almanac.composed();
+        almanac
+            .seeds
+            .iter()
+            .map(|&seed| map.get(seed))
+            .min()
+            .context("Empty location iterator")
+    }
+
+    fn part2(almanac: &Self::Input<'_>) -> Result<u64> {
+        almanac
+            .composed()
+            .map_ranges(seed_ranges(&almanac.seeds)?)
+            .first()
+            .map(|range| range.start)
+            .context("Empty finding minimum location")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected a line of seeds")]
+    MissingSeeds,
+    #[error("Invalid number '{0}'")]
+    InvalidNumber(String),
+    #[error("Expected a destination, source and length, found '{0}'")]
+    InvalidMapping(String),
+    #[error("Range of mapping '{0}' does not fit in 64 bits")]
+    MappingOverflow(String),
+}
+
+pub type Error = error::Error<ErrorKind>;
+
+//The numbers in source are moved to the same place in a range starting at dest
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Rule {
+    pub source: Range<u64>,
+    pub dest: u64,
+}
+
+impl Rule {
+    //only for numbers in source
+    fn apply(&self, num: u64) -> u64 {
+        self.dest + (num - self.source.start)
+    }
+}
+
+//One of the almanac's maps. Numbers that no rule covers map to themselves.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct IntervalMap {
+    //sorted by source, which never overlap or are empty
+    rules: Vec<Rule>,
+}
+
+impl IntervalMap {
+    //Where sources overlap, the earlier rule wins, so later rules only keep the parts no earlier rule covers
+    pub fn new(rules: Vec<Rule>) -> IntervalMap {
+        let mut kept: Vec<Rule> = Vec::new();
+        for rule in rules {
+            let mut pieces = Vec::new();
+            let mut start = rule.source.start;
+            let first = kept.partition_point(|earlier| earlier.source.end <= start);
+            for earlier in kept[first..]
+                .iter()
+                .take_while(|earlier| earlier.source.start < rule.source.end)
+            {
+                if start < earlier.source.start {
+                    pieces.push(start..earlier.source.start);
+                }
+                start = start.max(earlier.source.end);
+            }
+            if start < rule.source.end {
+                pieces.push(start..rule.source.end);
+            }
+            for piece in pieces {
+                let i = kept.partition_point(|earlier| earlier.source.start < piece.start);
+                let dest = rule.apply(piece.start);
+                kept.insert(
+                    i,
+                    Rule {
+                        source: piece,
+                        dest,
+                    },
+                );
+            }
+        }
+        IntervalMap { rules: kept }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn get(&self, num: u64) -> u64 {
+        let i = self.rules.partition_point(|rule| rule.source.end <= num);
+        match self.rules.get(i) {
+            Some(rule) if rule.source.contains(&num) => rule.apply(num),
+            _ => num,
+        }
+    }
+
+    //Splits the range wherever a rule starts or ends.
+    //Returns each piece along with where its start is moved to, which is itself in the gaps between rules.
+    fn segments(&self, range: Range<u64>) -> Vec<(Range<u64>, u64)> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+        let mut segments = Vec::new();
+        let mut start = range.start;
+        let first = self.rules.partition_point(|rule| rule.source.end <= start);
+        for rule in self.rules[first..]
+            .iter()
+            .take_while(|rule| rule.source.start < range.end)
+        {
+            if start < rule.source.start {
+                segments.push((start..rule.source.start, start));
+                start = rule.source.start;
+            }
+            let end = range.end.min(rule.source.end);
+            segments.push((start..end, rule.apply(start)));
+            start = end;
+        }
+        if start < range.end {
+            segments.push((start..range.end, start));
+        }
+        segments
+    }
+
+    //where every number in the range ends up, split into pieces that are each moved separately
+    pub fn map_range(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        self.segments(range)
+            .into_iter()
+            .map(|(source, dest)| dest..dest + (source.end - source.start))
+            .collect()
+    }
+
+    //Like map_range for every range, sorted with any overlaps merged
+    pub fn map_ranges(&self, ranges: impl IntoIterator<Item = Range<u64>>) -> Vec<Range<u64>> {
+        merge(ranges.into_iter().flat_map(|range| self.map_range(range)))
+    }
+
+    //Every number that ends up in the range, as sorted ranges.
+    //Numbers left where they are come from the range itself, and the rest from the rules moving them there.
+    pub fn preimage(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        let unmoved = self
+            .segments(range.clone())
+            .into_iter()
+            .filter(|(source, dest)| source.start == *dest)
+            .map(|(source, _)| source);
+        let moved = self.rules.iter().filter_map(|rule| {
+            let start = range.start.max(rule.dest);
+            let end = range.end.min(rule.apply(rule.source.end - 1) + 1);
+            (start < end).then(|| {
+                rule.source.start + (start - rule.dest)..rule.source.start + (end - rule.dest)
+            })
+        });
+        merge(unmoved.chain(moved))
+    }
+
+    //like preimage for every range
+    pub fn preimages(&self, ranges: impl IntoIterator<Item = Range<u64>>) -> Vec<Range<u64>> {
+        merge(ranges.into_iter().flat_map(|range| self.preimage(range)))
+    }
+
+    //A single map doing the same as this one followed by the next.
+    //Identity rules are left out and neighbouring rules moving by the same offset are joined,
+    //so any two maps doing the same are equal.
+    pub fn then(&self, next: &IntervalMap) -> IntervalMap {
+        let rules = self
+            .segments(0..u64::MAX)
+            .into_iter()
+            .flat_map(|(source, dest)| {
+                next.segments(dest..dest + (source.end - source.start))
+                    .into_iter()
+                    .map(move |(middle, dest_start)| Rule {
+                        source: source.start + (middle.start - dest)
+                            ..source.start + (middle.end - dest),
+                        dest: dest_start,
+                    })
+            })
+            .filter(|rule| rule.dest != rule.source.start)
+            .coalesce(|a, b| {
+                if a.source.end == b.source.start && a.apply(a.source.end - 1) + 1 == b.dest {
+                    Ok(Rule {
+                        source: a.source.start..b.source.end,
+                        dest: a.dest,
+                    })
+                } else {
+                    Err((a, b))
+                }
+            })
+            .collect();
+        IntervalMap { rules }
+    }
+}
+
+//a row for every rule, with how far it moves numbers
+impl Display for IntervalMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = iter::once(["start".to_string(), "end".to_string(), "offset".to_string()])
+            .chain(self.rules.iter().map(|rule| {
+                let offset = rule.dest as i128 - rule.source.start as i128;
+                [
+                    rule.source.start.to_string(),
+                    rule.source.end.to_string(),
+                    format!("{:+}", offset),
+                ]
+            }))
+            .collect_vec();
+        let widths = [0, 1, 2].map(|col| rows.iter().map(|row| row[col].len()).max().unwrap_or(0));
+        for row in rows {
+            writeln!(
+                f,
+                "{:>3$} {:>4$} {:>5$}",
+                row[0], row[1], row[2], widths[0], widths[1], widths[2]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+//sorts the ranges, joining those that overlap or touch and dropping empty ones
+pub fn merge(ranges: impl IntoIterator<Item = Range<u64>>) -> Vec<Range<u64>> {
+    ranges
+        .into_iter()
+        .filter(|range| !range.is_empty())
+        .sorted_by_key(|range| range.start)
+        .coalesce(|a, b| {
+            if b.start <= a.end {
+                Ok(a.start..a.end.max(b.end))
+            } else {
+                Err((a, b))
+            }
+        })
+        .collect()
+}
+
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    //from seed to soil, through to humidity to location
+    pub maps: Vec<IntervalMap>,
+}
+
+impl Almanac {
+    //applies every map in order, from seed to location
+    pub fn location(&self, seed: u64) -> u64 {
+        self.maps.iter().fold(seed, |acc, map| map.get(acc))
+    }
+
+    //every map in one, from seed straight to location
+    pub fn composed(&self) -> IntervalMap {
+        self.maps
+            .iter()
+            .fold(IntervalMap::default(), |acc, map| acc.then(map))
+    }
+
+    //every seed that ends up at any of the locations, as sorted ranges
+    pub fn seeds_reaching(
+        &self,
+        locations: impl IntoIterator<Item = Range<u64>>,
+    ) -> Vec<Range<u64>> {
+        self.maps
+            .iter()
+            .rev()
+            .fold(merge(locations), |acc, map| map.preimages(acc))
+    }
+
+    //every location any of the seeds end up at, as sorted ranges
+    pub fn location_ranges(&self, seeds: impl IntoIterator<Item = Range<u64>>) -> Vec<Range<u64>> {
+        self.maps
+            .iter()
+            .fold(merge(seeds), |acc, map| map.map_ranges(acc))
+    }
 }
 
-fn parse_transforms(input: &str) -> Result<Vec<impl Fn(u64) -> u64>> {
+fn parse_number(input: &str, num: &str) -> Result<u64, Error> {
+    num.parse()
+        .map_err(|_| Error::at(input, num, ErrorKind::InvalidNumber(num.to_string())))
+}
+
+pub fn parse_transforms(input: &str) -> Result<Vec<IntervalMap>, Error> {
     input
         .split("\n\n")
         .skip(1)
         .map(|map| {
-            let values = map
+            let rules = map
                 .lines()
                 .skip(1)
                 .map(|line| {
-                    line.split_whitespace()
-                        .map(str::parse)
+                    let error = |kind| Error::at(input, line, kind);
+                    let (dest, source, length) = line
+                        .split_whitespace()
+                        .map(|num| parse_number(input, num))
                         .process_results(|iter| iter.collect_tuple())?
-                        .context(format!("Error parsing almanac line: {}", line))
+                        .ok_or_else(|| error(ErrorKind::InvalidMapping(line.to_string())))?;
+                    //both ranges have to fit, so the transform never overflows
+                    if source.checked_add(length).is_none() || dest.checked_add(length).is_none() {
+                        return Err(error(ErrorKind::MappingOverflow(line.to_string())));
+                    }
+                    Ok(Rule {
+                        source: source..source + length,
+                        dest,
+                    })
                 })
-                .collect::<Result<Vec<_>>>()?;
+                .collect::<Result<Vec<_>, _>>()?;
 
-            Ok(move |num| {
-                for (dest, source, length) in &values {
-                    let source_range = *source..(*source + *length);
-                    if source_range.contains(&num) {
-                        return num + dest - source;
-                    }
-                }
-                num
-            })
+            Ok(IntervalMap::new(rules))
         })
         .collect()
 }
 
-fn parse_seeds(input: &str) -> Result<impl Iterator<Item = &str>> {
-    Ok(input
+pub fn parse_seeds(input: &str) -> Result<Vec<u64>, Error> {
+    input
         .lines()
         .next()
-        .context("Empty input")?
+        .ok_or_else(|| Error::new(Location::end_of(input), ErrorKind::MissingSeeds))?
         .split_whitespace()
-        .skip(1))
-}
-
-fn part1(input: &str) -> Result<u64> {
-    let transforms = parse_transforms(input)?;
-
-    parse_seeds(input)?
-        .map(str::parse)
-        .map_ok(|seed| {
-            transforms
-                .iter()
-                .fold(seed, |acc, transform| transform(acc))
-        })
-        .process_results(|iter| iter.min())?
-        .context("Empty location iterator")
+        .skip(1)
+        .map(|seed| parse_number(input, seed))
+        .collect()
 }
 
-fn part2(input: &str) -> Result<u64> {
-    let transforms = parse_transforms(input)?;
-    let seed_ranges = parse_seeds(input)?
+//interprets the seeds as pairs of (start, length)
+pub fn seed_ranges(seeds: &[u64]) -> Result<Vec<Range<u64>>> {
+    seeds
         .chunks(2)
-        .into_iter()
         .map(|chunk| {
-            let (start, length) = chunk
-                .collect_tuple()
-                .context("Error chunking seeds into pairs")?;
-            let start = start.parse::<u64>()?;
-            let length = length.parse::<u64>()?;
-            Ok(start..start + length)
+            let &[start, length] = chunk else {
+                bail!("Error chunking seeds into pairs");
+            };
+            let end = start
+                .checked_add(length)
+                .context("Seed range does not fit in 64 bits")?;
+            Ok(start..end)
         })
-        .collect::<Result<Vec<_>>>()?;
+        .collect()
+}
 
-    seed_ranges
-        .into_par_iter()
-        .flatten()
-        .map(|seed| {
-            transforms
-                .iter()
-                .fold(seed, |acc, transform| transform(acc))
-        })
-        .min()
-        .context("Empty finding minimum location")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day5::solve_part1(EXAMPLE)?, 35);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day5::solve_part2(EXAMPLE)?, 46);
+        Ok(())
+    }
+
+    #[test]
+    fn seed_locations() -> Result<()> {
+        let almanac = Day5::parse(EXAMPLE)?;
+        let locations = almanac
+            .seeds
+            .iter()
+            .map(|&seed| almanac.location(seed))
+            .collect::<Vec<_>>();
+        assert_eq!(locations, [82, 43, 86, 35]);
+        Ok(())
+    }
+
+    #[test]
+    fn map_range_splits() -> Result<()> {
+        let almanac = Day5::parse(EXAMPLE)?;
+        //seed-to-soil moves 50..98 up by 2 and 98..100 down to 50
+        let map = &almanac.maps[0];
+        assert_eq!(map.map_range(40..105), [40..50, 52..100, 50..52, 100..105]);
+        assert_eq!(map.map_ranges([0..10, 60..70]), [0..10, 62..72]);
+        assert_eq!(map.map_ranges([40..105, 200..300]), [40..105, 200..300]);
+        Ok(())
+    }
+
+    #[test]
+    fn location_ranges() -> Result<()> {
+        let almanac = Day5::parse(EXAMPLE)?;
+        let locations = (0..100)
+            .map(|seed| almanac.location(seed))
+            .map(|l| l..l + 1);
+        assert_eq!(
+            almanac.location_ranges((0..100).map(|seed| seed..seed + 1)),
+            merge(locations)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn composed() -> Result<()> {
+        let almanac = Day5::parse(EXAMPLE)?;
+        let map = almanac.composed();
+        for seed in 0..200 {
+            assert_eq!(map.get(seed), almanac.location(seed));
+        }
+        //every rule moves its numbers, by a different offset than the rule before it if they touch
+        assert_eq!(map.rules().len(), 20);
+        assert_eq!(
+            map.to_string().lines().take(3).collect_vec(),
+            ["start end offset", "    0  14    +22", "   14  15    +29"]
+        );
+
+        //swapping two ranges and swapping them back is the same as not moving them
+        let there_and_back = Day5::parse(
+            "seeds: 1\n\na-to-b map:\n20 10 5\n10 20 5\n\nb-to-c map:\n10 20 5\n20 10 5",
+        )?;
+        assert_eq!(there_and_back.composed(), IntervalMap::default());
+        let split = Day5::parse("seeds: 1\n\na-to-b map:\n20 10 5\n25 15 5")?;
+        let joined = Day5::parse("seeds: 1\n\na-to-b map:\n20 10 10")?;
+        assert_eq!(split.composed(), joined.composed());
+        Ok(())
+    }
+
+    #[test]
+    fn seeds_reaching() -> Result<()> {
+        let almanac = Day5::parse(EXAMPLE)?;
+        //seed 82 is the closest to location 46, and numbers past every rule go straight through
+        assert_eq!(
+            almanac.seeds_reaching([46..47, 150..200]),
+            [82..83, 150..200]
+        );
+        assert!(almanac.seeds_reaching(iter::once(0..0)).is_empty());
+
+        //seeds from 100 on stay where they are, so only those below are checked one by one
+        let map = almanac.composed();
+        for locations in [0..10, 35..50, 60..100, 99..101] {
+            let reaching = (0..100)
+                .filter(|&seed| locations.contains(&almanac.location(seed)))
+                .map(|seed| seed..seed + 1)
+                .chain(iter::once(locations.start.max(100)..locations.end.max(100)));
+            let reaching = merge(reaching);
+            assert_eq!(
+                almanac.seeds_reaching(iter::once(locations.clone())),
+                reaching
+            );
+            assert_eq!(map.preimage(locations), reaching);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn overlapping_mappings() -> Result<()> {
+        //the first rule that covers a number is the one that moves it
+        let input = "seeds: 1\n\na-to-b map:\n0 10 5\n20 12 5\n50 8 10";
+        let almanac = Day5::parse(input)?;
+        let map = &almanac.maps[0];
+        let moved = (7..20).map(|num| map.get(num)).collect_vec();
+        assert_eq!(moved, [7, 50, 51, 0, 1, 2, 3, 4, 23, 24, 59, 18, 19]);
+        let sources = map
+            .rules()
+            .iter()
+            .map(|rule| rule.source.clone())
+            .collect_vec();
+        assert_eq!(sources, [8..10, 10..15, 15..17, 17..18]);
+        Ok(())
+    }
+
+    #[test]
+    fn mapping_overflow() {
+        let input = "seeds: 1\n\na-to-b map:\n0 18446744073709551615 2";
+        let error = Day5::parse(input).err().map(|e| e.location);
+        assert_eq!(error, Some(Location::new(4, 1)));
+    }
 }
@@ -1,45 +1,131 @@
-use anyhow::{anyhow, Context, Result};
+use crate::{
+    error::{self, Location},
+    Solution,
+};
+use anyhow::Result;
 use itertools::Itertools;
 use num_traits::PrimInt;
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day6.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input<'a> = Races;
+    type Error = Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        let (times, distances) = input
+            .lines()
+            .map(|line| {
+                line.split_whitespace()
+                    .skip(1)
+                    .map(|num| {
+                        num.parse().map_err(|_| {
+                            Error::at(input, num, ErrorKind::InvalidNumber(num.to_string()))
+                        })
+                    })
+                    .collect::<Result<Vec<u64>, _>>()
+            })
+            .collect_tuple()
+            .ok_or_else(|| Error::new(Location::end_of(input), ErrorKind::ExpectedTwoLines))?;
+        Ok(Races {
+            times: times?,
+            distances: distances?,
+        })
+    }
+
+    fn part1(races: &Self::Input<'_>) -> Result<usize> {
+        let product = races
+            .times
+            .iter()
+            .zip(&races.distances)
+            .map(|(&time, &distance)| ways_to_win(time, distance))
+            .product();
+        Ok(product)
+    }
+
+    fn part2(races: &Self::Input<'_>) -> Result<usize> {
+        //the spaces between numbers are ignored, so it is actually one race
+        let time = races.times.iter().join("").parse::<u64>()?;
+        let distance = races.distances.iter().join("").parse::<u64>()?;
+        Ok(ways_to_win(time, distance))
+    }
 }
 
-fn ways_to_win<I: PrimInt>(time: I, winning_distance: I) -> usize {
-    num_iter::range(I::one(), time)
-        .filter(|&speed| speed * (time - speed) > winning_distance)
-        .count()
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected exactly two lines, one of times and one of distances")]
+    ExpectedTwoLines,
+    #[error("Invalid number '{0}'")]
+    InvalidNumber(String),
 }
 
-fn part1(input: &str) -> Result<usize> {
-    let (times, distances) = input
-        .lines()
-        .map(|line| line.split_whitespace().skip(1).map(str::parse::<u32>))
-        .collect_tuple()
-        .context("Error: expected two lines in input")?;
-    times
-        .zip(distances)
-        .map(|(time, distance)| Ok(ways_to_win(time?, distance?)))
-        .process_results(|iter| iter.product())
+pub type Error = error::Error<ErrorKind>;
+
+pub struct Races {
+    pub times: Vec<u64>,
+    pub distances: Vec<u64>,
 }
 
-fn part2(input: &str) -> Result<usize> {
-    let [time, distance] = input
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .skip(1)
-                .collect::<String>()
-                .parse::<u64>()
-                .map_err(Into::into)
+pub fn ways_to_win<I: PrimInt>(time: I, winning_distance: I) -> usize {
+    num_iter::range(I::one(), time)
+        //a distance too big to fit in I is certainly far enough
+        .filter(|&speed| {
+            speed
+                .checked_mul(&(time - speed))
+                .is_none_or(|distance| distance > winning_distance)
         })
-        .collect::<Result<Vec<_>>>()?
-        .try_into()
-        .map_err(|_| anyhow!("Error parsing input"))?;
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day6::solve_part1(EXAMPLE)?, 288);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day6::solve_part2(EXAMPLE)?, 71503);
+        Ok(())
+    }
+
+    #[test]
+    fn single_races() {
+        assert_eq!(ways_to_win(7, 9), 4);
+        assert_eq!(ways_to_win(15, 40), 8);
+        assert_eq!(ways_to_win(30, 200), 9);
+    }
+
+    //moves the boat one millisecond at a time, the way the puzzle describes it
+    fn simulate_ways_to_win(time: u64, winning_distance: u64) -> usize {
+        (0..=time)
+            .filter(|&hold| {
+                let distance = (hold..time).map(|_| hold).sum::<u64>();
+                distance > winning_distance
+            })
+            .count()
+    }
 
-    Ok(ways_to_win(time, distance))
+    proptest! {
+        #[test]
+        fn matches_simulation(time in 0..100_u64, winning_distance in 0..3000_u64) {
+            prop_assert_eq!(
+                ways_to_win(time, winning_distance),
+                simulate_ways_to_win(time, winning_distance)
+            );
+        }
+    }
 }
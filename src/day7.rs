@@ -1,21 +1,85 @@
-use std::{cmp::Ordering, hash::Hash};
+use std::{cmp::Ordering, collections::HashMap, hash::Hash, io::BufRead};
 
-use anyhow::{anyhow, Context, Result};
+use crate::{
+    error::{self, Location},
+    stream::{self, Streaming},
+    Solution,
+};
+use anyhow::Result;
 use itertools::Itertools;
 use tap::Pipe;
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day7.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input<'a> = Vec<(Hand, u32)>;
+    type Error = Error;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_line(line).map_err(|e| e.offset_by(Location::new(i + 1, 1))))
+            .collect()
+    }
+
+    fn part1(pairs: &Self::Input<'_>) -> Result<u64> {
+        Ok(calculate_winnings(pairs.iter().cloned()))
+    }
+
+    fn part2(pairs: &Self::Input<'_>) -> Result<u64> {
+        let winnings = pairs
+            .iter()
+            .cloned()
+            .update(|(hand, _)| inject_jokers(hand))
+            .pipe(calculate_winnings);
+        Ok(winnings)
+    }
 }
 
-#[derive(PartialEq, Eq, Hash)]
-struct Card(char);
+impl Streaming for Day7 {
+    fn stream_part1(input: impl BufRead) -> Result<u64> {
+        stream_winnings(stream::parse_lines(input, parse_line))
+    }
+
+    fn stream_part2(input: impl BufRead) -> Result<u64> {
+        let pairs = stream::parse_lines(input, parse_line).update(|pair| {
+            if let Ok((hand, _)) = pair {
+                inject_jokers(hand)
+            }
+        });
+        stream_winnings(pairs)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected a hand and a bid, found '{0}'")]
+    InvalidLine(String),
+    #[error("Invalid bid '{0}'")]
+    InvalidBid(String),
+    #[error("Unexpected card '{0}'")]
+    InvalidCard(char),
+    #[error("A hand must be 5 cards, found {0}")]
+    WrongHandSize(usize),
+}
+
+pub type Error = error::Error<ErrorKind>;
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+pub struct Card(pub char);
 
 impl Card {
-    fn strength(&self) -> u32 {
+    //None if the symbol is not a card
+    pub fn new(symbol: char) -> Option<Card> {
+        matches!(symbol, '2'..='9' | 'T' | 'J' | 'Q' | 'K' | 'A').then_some(Card(symbol))
+    }
+
+    pub fn strength(&self) -> u32 {
         match self.0 {
             'T' => 10,
             'J' => 11,
@@ -23,7 +87,8 @@ impl Card {
             'K' => 13,
             'A' => 14,
             'j' => 0, //lowercase j represents a joker
-            c => c.to_digit(10).expect("Unexpected card symbol"),
+            //anything else made by Card::new is a digit
+            c => c.to_digit(10).unwrap_or(0),
         }
     }
 }
@@ -41,7 +106,7 @@ impl PartialOrd for Card {
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
+pub enum HandType {
     HighCard,
     OnePair,
     TwoPair,
@@ -51,13 +116,13 @@ enum HandType {
     FiveOfAKind,
 }
 
-#[derive(Eq)]
-struct Hand {
-    cards: [Card; 5],
+#[derive(Eq, Clone)]
+pub struct Hand {
+    pub cards: [Card; 5],
 }
 
 impl Hand {
-    fn hand_type(&self) -> HandType {
+    pub fn hand_type(&self) -> HandType {
         let Some(most_common_card) = self
             .cards
             .iter()
@@ -126,23 +191,29 @@ fn mode<E: Hash + Eq>(iter: impl Iterator<Item = E>) -> Option<E> {
         .map(|(elem, _)| elem)
 }
 
-fn parse_line(line: &str) -> Result<(Hand, u32)> {
+//locations in errors are relative to the line
+pub fn parse_line(line: &str) -> Result<(Hand, u32), Error> {
+    let error = |token: &str, kind| Error::at(line, token, kind);
+
     let (cards, bid) = line
         .split_whitespace()
         .collect_tuple()
-        .context("Error: input line must have 2 tokens")?;
-    let bid = bid.parse()?;
-    let cards = cards
-        .chars()
-        .map(Card)
-        .collect_vec()
+        .ok_or_else(|| error(line, ErrorKind::InvalidLine(line.to_string())))?;
+    let bid = bid
+        .parse()
+        .map_err(|_| error(bid, ErrorKind::InvalidBid(bid.to_string())))?;
+    let hand = cards
+        .char_indices()
+        .map(|(i, symbol)| {
+            Card::new(symbol).ok_or_else(|| error(&cards[i..], ErrorKind::InvalidCard(symbol)))
+        })
+        .collect::<Result<Vec<_>, _>>()?
         .try_into()
-        .map_err(|_| anyhow!("Error: a hand must be 5 cards"))?;
-    let hand = Hand { cards };
-    Ok((hand, bid))
+        .map_err(|hand: Vec<_>| error(cards, ErrorKind::WrongHandSize(hand.len())))?;
+    Ok((Hand { cards: hand }, bid))
 }
 
-fn inject_jokers(hand: &mut Hand) {
+pub fn inject_jokers(hand: &mut Hand) {
     for card in &mut hand.cards {
         if *card == Card('J') {
             *card = Card('j');
@@ -150,26 +221,82 @@ fn inject_jokers(hand: &mut Hand) {
     }
 }
 
-fn calculate_winnings(pairs: impl Iterator<Item = (Hand, u32)>) -> u32 {
+//identical hands are ranked in the order they were dealt
+pub fn calculate_winnings(pairs: impl Iterator<Item = (Hand, u32)>) -> u64 {
     pairs
-        .sorted_unstable_by(|pair, other| pair.0.cmp(&other.0))
+        .sorted_by(|pair, other| pair.0.cmp(&other.0))
         .map(|(_, bid)| bid)
         .enumerate()
-        .map(|(i, bid)| (i as u32 + 1) * bid)
+        .map(|(i, bid)| (i as u64 + 1) * bid as u64)
         .sum()
 }
 
-fn part1(input: &str) -> Result<u32> {
-    input
-        .lines()
-        .map(parse_line)
-        .process_results(|pairs| calculate_winnings(pairs))
+//the bids placed on one distinct hand
+#[derive(Default)]
+struct Bids {
+    count: u64,
+    sum: u64,
+    //every bid times the number of identical hands dealt before it
+    weighted: u64,
 }
 
-fn part2(input: &str) -> Result<u32> {
-    input.lines().map(parse_line).process_results(|pairs| {
-        pairs
-            .update(|(hand, _)| inject_jokers(hand))
-            .pipe(calculate_winnings)
-    })
+//Like calculate_winnings, but only remembers the bids on each distinct hand, so any number of hands
+//fits in memory. There are at most 13^5 distinct hands, or a few more with jokers.
+pub fn stream_winnings(pairs: impl Iterator<Item = Result<(Hand, u32)>>) -> Result<u64> {
+    let mut hands = HashMap::<[Card; 5], Bids>::new();
+    for pair in pairs {
+        let (hand, bid) = pair?;
+        let bids = hands.entry(hand.cards).or_default();
+        bids.weighted += bids.count * bid as u64;
+        bids.count += 1;
+        bids.sum += bid as u64;
+    }
+
+    //the number of hands ranked below the current one
+    let mut ranked = 0;
+    let winnings = hands
+        .into_iter()
+        .map(|(cards, bids)| (Hand { cards }, bids))
+        .sorted_unstable_by(|(hand, _), (other, _)| hand.cmp(other))
+        .map(|(_, bids)| {
+            let winnings = (ranked + 1) * bids.sum + bids.weighted;
+            ranked += bids.count;
+            winnings
+        })
+        .sum();
+    Ok(winnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day7::solve_part1(EXAMPLE)?, 6440);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day7::solve_part2(EXAMPLE)?, 5905);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_cards() {
+        let error = Day7::parse("32T3K 765\nT55X5 684").err();
+        let expected = Error::new(Location::new(2, 4), ErrorKind::InvalidCard('X'));
+        assert_eq!(error, Some(expected));
+
+        let error = Day7::parse("32T3 765").err().map(|e| e.kind);
+        assert_eq!(error, Some(ErrorKind::WrongHandSize(4)));
+    }
 }
@@ -1,99 +1,204 @@
-use anyhow::{bail, Context, Result};
+use crate::{
+    error::{self, Location},
+    geometry::Turn,
+    Solution,
+};
+use anyhow::{Context, Result};
 use itertools::Itertools;
 use num_integer::Integer;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day8.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input<'a> = Network<'a>;
+    type Error = Error;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        let steps = parse_steps(input)?;
+        let map = parse_map(input)?;
+        Ok(Network { steps, map })
+    }
+
+    fn part1(network: &Self::Input<'_>) -> Result<usize> {
+        count_steps(&network.steps, "AAA", &network.map, |location| {
+            location == "ZZZ"
+        })
+        .context("ZZZ can not be reached from AAA")
+    }
+
+    fn part2(network: &Self::Input<'_>) -> Result<usize> {
+        let start_locations = network
+            .map
+            .keys()
+            .filter(|location| location.ends_with('A'));
+
+        let counts = start_locations
+            .map(|start| {
+                count_steps(&network.steps, start, &network.map, |location| {
+                    location.ends_with('Z')
+                })
+                .with_context(|| format!("No location ending in Z can be reached from {}", start))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        counts
+            .into_iter()
+            .reduce(|a, b| a.lcm(&b))
+            .context("No start locations")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Expected a line of steps")]
+    MissingSteps,
+    #[error("Unexpected step '{0}'")]
+    InvalidStep(char),
+    #[error("Expected a node like 'AAA = (BBB, CCC)', found '{0}'")]
+    InvalidNode(String),
+    #[error("Node '{0}' is never defined")]
+    UnknownNode(String),
 }
 
-#[derive(Debug, Clone)]
-enum Direction {
-    Left,
-    Right,
+pub type Error = error::Error<ErrorKind>;
+
+pub struct Network<'a> {
+    pub steps: Vec<Turn>,
+    pub map: HashMap<&'a str, (&'a str, &'a str)>,
 }
 
-fn parse_steps(input: &str) -> Result<impl Iterator<Item = Direction> + Clone> {
-    input
+pub fn parse_steps(input: &str) -> Result<Vec<Turn>, Error> {
+    let line = input
         .lines()
         .next()
-        .context("Empty input")?
-        .chars()
-        .map(|c| {
-            Ok(match c {
-                'L' => Direction::Left,
-                'R' => Direction::Right,
-                _ => bail!("Unexpected character: {}", c),
-            })
+        .ok_or_else(|| Error::new(Location::end_of(input), ErrorKind::MissingSteps))?;
+    line.char_indices()
+        .map(|(i, c)| {
+            Turn::from_letter(c)
+                .ok_or_else(|| Error::at(input, &line[i..], ErrorKind::InvalidStep(c)))
         })
-        .collect::<Result<Vec<_>>>()
-        .map(|steps| steps.into_iter().cycle())
+        .collect()
 }
 
-fn parse_map(input: &str) -> Result<HashMap<&str, (&str, &str)>> {
-    input
+pub fn parse_map(input: &str) -> Result<HashMap<&str, (&str, &str)>, Error> {
+    let map = input
         .lines()
         .skip(2)
         .map(|line| {
-            let Some((source, left, right)) = line
+            let (source, left, right) = line
                 .split(|c: char| !c.is_alphanumeric())
                 .filter(|s| !s.is_empty())
                 .collect_tuple()
-            else {
-                bail!("Error parsing line: {}", line);
-            };
+                .ok_or_else(|| Error::at(input, line, ErrorKind::InvalidNode(line.to_string())))?;
             Ok((source, (left, right)))
         })
-        .collect()
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    //every node has to lead somewhere, so walking the map never gets stuck
+    let unknown = map
+        .values()
+        .flat_map(|&(left, right)| [left, right])
+        .filter(|node| !map.contains_key(node))
+        .min_by_key(|node| node.as_ptr());
+    match unknown {
+        Some(node) => Err(Error::at(
+            input,
+            node,
+            ErrorKind::UnknownNode(node.to_string()),
+        )),
+        None => Ok(map),
+    }
 }
 
-fn count_steps(
-    steps: impl Iterator<Item = Direction>,
+//follows the steps, repeating them forever, until is_end is true.
+//None if that never happens.
+pub fn count_steps(
+    steps: &[Turn],
     start: &str,
     map: &HashMap<&str, (&str, &str)>,
     is_end: impl Fn(&str) -> bool,
-) -> usize {
-    steps
-        .scan(start, |location, direction| {
-            if is_end(location) {
-                return None;
-            }
-            let (left, right) = map[location];
-            match direction {
-                Direction::Left => *location = left,
-                Direction::Right => *location = right,
-            }
-            Some(())
-        })
-        .count()
+) -> Option<usize> {
+    let mut seen = HashSet::new();
+    let mut location = start;
+    for (count, (i, turn)) in steps.iter().enumerate().cycle().enumerate() {
+        if is_end(location) {
+            return Some(count);
+        }
+        //being in the same place at the same point in the steps means going around in circles
+        if !seen.insert((location, i)) {
+            return None;
+        }
+        let &(left, right) = map.get(location)?;
+        location = match turn {
+            Turn::Left => left,
+            Turn::Right => right,
+        };
+    }
+    //there are no steps to take
+    is_end(location).then_some(0)
 }
 
-fn part1(input: &str) -> Result<usize> {
-    let map = parse_map(input)?;
-    let steps = parse_steps(input)?;
-    let count = count_steps(steps, "AAA", &map, |location| location == "ZZZ");
-    Ok(count)
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-fn part2(input: &str) -> Result<usize> {
-    let map = parse_map(input)?;
-    let steps = parse_steps(input)?;
-    let start_locations = input.lines().skip(2).filter_map(|line| {
-        line.split_whitespace()
-            .next()
-            .filter(|location| location.ends_with('A'))
-    });
-
-    let count = start_locations
-        .map(|start| {
-            count_steps(steps.clone(), start, &map, |location| {
-                location.ends_with('Z')
-            })
-        })
-        .reduce(|a, b| a.lcm(&b))
-        .context("No start locations")?;
-    Ok(count)
+    const EXAMPLE1: &str = "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)";
+
+    const EXAMPLE2: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+
+    const EXAMPLE3: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+    #[test]
+    fn part1_examples() -> Result<()> {
+        assert_eq!(Day8::solve_part1(EXAMPLE1)?, 2);
+        assert_eq!(Day8::solve_part1(EXAMPLE2)?, 6);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day8::solve_part2(EXAMPLE3)?, 6);
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_node() {
+        let error = Day8::parse("L\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)").err();
+        let expected = Error::new(Location::new(3, 8), ErrorKind::UnknownNode("BBB".into()));
+        assert_eq!(error, Some(expected));
+    }
+
+    #[test]
+    fn never_ending() {
+        let network = Day8::parse("LR\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)").unwrap();
+        assert!(Day8::part1(&network).is_err());
+    }
 }
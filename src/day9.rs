@@ -1,72 +1,139 @@
-use anyhow::Result;
-use itertools::Itertools;
-use std::iter::successors;
-
-fn main() -> Result<()> {
-    let input = include_str!("../inputs/day9.txt");
-    println!("Part 1 = {}", part1(input)?);
-    println!("Part 2 = {}", part2(input)?);
-    Ok(())
+use crate::{
+    error::{self, Location},
+    stream::{self, Streaming},
+    Solution,
+};
+use anyhow::{Context, Result};
+use itertools::{process_results, Itertools};
+use std::io::BufRead;
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input<'a> = Vec<Vec<i32>>;
+    type Error = Error;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse_history(line).map_err(|e| e.offset_by(Location::new(i + 1, 1))))
+            .collect()
+    }
+
+    fn part1(histories: &Self::Input<'_>) -> Result<i64> {
+        total(histories.iter().map(|nums| predict_next(nums))).context("Too large to predict")
+    }
+
+    fn part2(histories: &Self::Input<'_>) -> Result<i64> {
+        total(histories.iter().map(|nums| predict_prev(nums))).context("Too large to predict")
+    }
 }
 
-fn predict_next(nums: Vec<i32>) -> i32 {
-    successors(Some(nums), |nums| {
-        let row = nums
-            .iter()
-            .tuple_windows()
-            .map(|(prev, next)| next - prev)
-            .collect::<Vec<_>>();
-        if row.iter().all(|num| *num == 0) {
-            return None;
-        }
-        Some(row)
-    })
-    .map(|nums| nums.last().copied().expect("nums should not be empty"))
-    .sum()
+impl Streaming for Day9 {
+    fn stream_part1(input: impl BufRead) -> Result<i64> {
+        let predictions =
+            stream::parse_lines(input, parse_history).map_ok(|nums| predict_next(&nums));
+        process_results(predictions, |predictions| total(predictions))?
+            .context("Too large to predict")
+    }
+
+    fn stream_part2(input: impl BufRead) -> Result<i64> {
+        let predictions =
+            stream::parse_lines(input, parse_history).map_ok(|nums| predict_prev(&nums));
+        process_results(predictions, |predictions| total(predictions))?
+            .context("Too large to predict")
+    }
+}
+
+//None if any prediction, or their sum, does not fit
+fn total(mut predictions: impl Iterator<Item = Option<i64>>) -> Option<i64> {
+    predictions.try_fold(0_i64, |sum, prediction| sum.checked_add(prediction?))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Invalid number '{0}'")]
+    InvalidNumber(String),
 }
 
-fn predict_prev(nums: Vec<i32>) -> i32 {
-    successors(Some(nums), |nums| {
-        let row = nums
+pub type Error = error::Error<ErrorKind>;
+
+//locations in errors are relative to the line
+pub fn parse_history(line: &str) -> Result<Vec<i32>, Error> {
+    line.split_whitespace()
+        .map(|num| {
+            num.parse()
+                .map_err(|_| Error::at(line, num, ErrorKind::InvalidNumber(num.to_string())))
+        })
+        .collect()
+}
+
+//The history followed by each row of differences, down to the first row of all zeros.
+//None if the differences do not fit.
+fn differences(nums: &[i32]) -> Option<Vec<Vec<i64>>> {
+    let mut rows = vec![nums.iter().map(|&num| i64::from(num)).collect_vec()];
+    while let Some(row) = rows.last().filter(|row| row.iter().any(|&num| num != 0)) {
+        let differences = row
             .iter()
             .tuple_windows()
-            .map(|(prev, next)| next - prev)
-            .collect::<Vec<_>>();
-        if row.iter().all(|num| *num == 0) {
-            return None;
-        }
-        Some(row)
+            .map(|(prev, next)| next.checked_sub(*prev))
+            .collect::<Option<Vec<_>>>()?;
+        rows.push(differences);
+    }
+    Some(rows)
+}
+
+//an empty history predicts 0
+pub fn predict_next(nums: &[i32]) -> Option<i64> {
+    differences(nums)?.iter().try_fold(0_i64, |sum, row| {
+        sum.checked_add(row.last().copied().unwrap_or(0))
     })
-    .map(|nums| nums[0])
-    .collect_vec()
-    .into_iter()
-    .rev()
-    .reduce(|a, b| b - a)
-    .expect("Vec should not be empty")
 }
 
-fn part1(input: &str) -> Result<i32> {
-    input
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(str::parse::<i32>)
-                .collect::<Result<Vec<_>, _>>()
+pub fn predict_prev(nums: &[i32]) -> Option<i64> {
+    differences(nums)?
+        .iter()
+        .rev()
+        .try_fold(0_i64, |prev, row| {
+            row.first().copied().unwrap_or(0).checked_sub(prev)
         })
-        .map_ok(predict_next)
-        .sum::<Result<_, _>>()
-        .map_err(Into::into)
 }
 
-fn part2(input: &str) -> Result<i32> {
-    input
-        .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(str::parse::<i32>)
-                .collect::<Result<Vec<_>, _>>()
-        })
-        .map_ok(predict_prev)
-        .sum::<Result<_, _>>()
-        .map_err(Into::into)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
+    #[test]
+    fn part1_example() -> Result<()> {
+        assert_eq!(Day9::solve_part1(EXAMPLE)?, 114);
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example() -> Result<()> {
+        assert_eq!(Day9::solve_part2(EXAMPLE)?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn too_large_to_predict() -> Result<()> {
+        //the differences fit in 64 bits even though the numbers only just fit in 32
+        assert_eq!(Day9::solve_part1("2147483647 -2147483648 5")?, 8589934606);
+
+        //alternating numbers double their differences every row
+        let input = [i32::MAX, i32::MIN].iter().cycle().take(40).join(" ");
+        let error = Day9::solve_part1(&input).err().map(|e| e.to_string());
+        assert_eq!(error.as_deref(), Some("Too large to predict"));
+        Ok(())
+    }
 }
@@ -0,0 +1,110 @@
+use crate::grid::Position;
+use std::fmt::{self, Display};
+
+//1-based line and column of the character where something went wrong
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn new(line: usize, column: usize) -> Self {
+        Location { line, column }
+    }
+
+    //where token starts in text, given that token is a slice of text
+    pub fn of(text: &str, token: &str) -> Self {
+        let offset = (token.as_ptr() as usize)
+            .checked_sub(text.as_ptr() as usize)
+            .filter(|&offset| text.is_char_boundary(offset))
+            .unwrap_or(0);
+        let before = &text[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Location {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    //just past the last character of text, for things that are missing
+    pub fn end_of(text: &str) -> Self {
+        Location::of(text, &text[text.len()..])
+    }
+}
+
+//rows and columns of a grid are lines and columns of the input it was parsed from
+impl From<Position> for Location {
+    fn from((row, col): Position) -> Self {
+        Location::new(row + 1, col + 1)
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+//Something wrong with the input, where kind is the day specific reason
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{kind} at {location}")]
+pub struct Error<K> {
+    pub location: Location,
+    pub kind: K,
+}
+
+impl<K> Error<K> {
+    pub fn new(location: impl Into<Location>, kind: K) -> Self {
+        Error {
+            location: location.into(),
+            kind,
+        }
+    }
+
+    //the error for token, which is a slice of text
+    pub fn at(text: &str, token: &str, kind: K) -> Self {
+        Error::new(Location::of(text, token), kind)
+    }
+
+    pub fn map<L>(self, f: impl FnOnce(K) -> L) -> Error<L> {
+        Error {
+            location: self.location,
+            kind: f(self.kind),
+        }
+    }
+
+    //for errors found in a piece of the input that starts at the given location
+    pub fn offset_by(mut self, start: Location) -> Self {
+        if self.location.line == 1 {
+            self.location.column += start.column - 1;
+        }
+        self.location.line += start.line - 1;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locations() {
+        let text = "first\nsecond line\nthird";
+        let token = &text[13..17];
+        assert_eq!(token, "line");
+        assert_eq!(Location::of(text, token), Location::new(2, 8));
+        assert_eq!(Location::of(text, text), Location::new(1, 1));
+        assert_eq!(Location::end_of(text), Location::new(3, 6));
+        //tokens that are not part of the text are placed at the start
+        assert_eq!(Location::of(text, "line"), Location::new(1, 1));
+    }
+
+    #[test]
+    fn offsets() {
+        let error = Error::new(Location::new(1, 3), ()).offset_by(Location::new(4, 5));
+        assert_eq!(error.location, Location::new(4, 7));
+        let error = Error::new(Location::new(2, 3), ()).offset_by(Location::new(4, 5));
+        assert_eq!(error.location, Location::new(5, 3));
+    }
+}
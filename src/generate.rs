@@ -0,0 +1,1165 @@
+use itertools::{chain, Itertools};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    iter,
+    ops::{Range, RangeInclusive},
+};
+
+//Random but structurally valid puzzle inputs, so the solutions can be run on more than one input.
+//What size means depends on the day, usually it is the number of lines or the side of the grid.
+pub struct Generator {
+    pub day: u8,
+    //about the size of a real input
+    pub default_size: usize,
+    //larger sizes would run out of unique names or the like
+    pub max_size: Option<usize>,
+    generate: fn(&mut StdRng, usize) -> String,
+}
+
+impl Generator {
+    pub fn generate(&self, size: usize, seed: u64) -> String {
+        (self.generate)(&mut StdRng::seed_from_u64(seed), size)
+    }
+}
+
+pub const GENERATORS: &[Generator] = &[
+    Generator {
+        day: 1,
+        default_size: 1000,
+        max_size: None,
+        generate: day1,
+    },
+    Generator {
+        day: 2,
+        default_size: 100,
+        max_size: None,
+        generate: day2,
+    },
+    Generator {
+        day: 3,
+        default_size: 140,
+        max_size: None,
+        generate: day3,
+    },
+    Generator {
+        day: 4,
+        default_size: 200,
+        max_size: None,
+        generate: day4,
+    },
+    Generator {
+        day: 5,
+        default_size: 30,
+        max_size: None,
+        generate: day5,
+    },
+    Generator {
+        day: 6,
+        default_size: 3,
+        max_size: None,
+        generate: day6,
+    },
+    Generator {
+        day: 7,
+        default_size: 1000,
+        max_size: None,
+        generate: day7,
+    },
+    Generator {
+        day: 8,
+        default_size: 300,
+        max_size: Some(DAY8_MAX_SIZE),
+        generate: day8,
+    },
+    Generator {
+        day: 9,
+        default_size: 200,
+        max_size: None,
+        generate: day9,
+    },
+    Generator {
+        day: 10,
+        default_size: 70,
+        max_size: None,
+        generate: day10,
+    },
+    Generator {
+        day: 11,
+        default_size: 140,
+        max_size: None,
+        generate: day11,
+    },
+    Generator {
+        day: 12,
+        default_size: 1000,
+        max_size: None,
+        generate: day12,
+    },
+    Generator {
+        day: 13,
+        default_size: 100,
+        max_size: None,
+        generate: day13,
+    },
+    Generator {
+        day: 14,
+        default_size: 100,
+        max_size: None,
+        generate: day14,
+    },
+    Generator {
+        day: 15,
+        default_size: 4000,
+        max_size: None,
+        generate: day15,
+    },
+    Generator {
+        day: 16,
+        default_size: 110,
+        max_size: None,
+        generate: day16,
+    },
+    Generator {
+        day: 17,
+        default_size: 141,
+        max_size: None,
+        generate: day17,
+    },
+    Generator {
+        day: 18,
+        default_size: 100,
+        max_size: None,
+        generate: day18,
+    },
+    Generator {
+        day: 19,
+        default_size: 500,
+        max_size: None,
+        generate: day19,
+    },
+    Generator {
+        day: 20,
+        default_size: 4,
+        max_size: None,
+        generate: day20,
+    },
+    Generator {
+        day: 21,
+        default_size: 131,
+        max_size: None,
+        generate: day21,
+    },
+    Generator {
+        day: 22,
+        default_size: 1200,
+        max_size: None,
+        generate: day22,
+    },
+    Generator {
+        day: 23,
+        default_size: 6,
+        max_size: None,
+        generate: day23,
+    },
+    Generator {
+        day: 24,
+        default_size: 300,
+        max_size: None,
+        generate: day24,
+    },
+    Generator {
+        day: 25,
+        default_size: 1500,
+        max_size: None,
+        generate: day25,
+    },
+];
+
+pub fn get(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|generator| generator.day == day)
+}
+
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+fn random_word(rng: &mut StdRng, alphabet: &[u8], length: RangeInclusive<usize>) -> String {
+    (0..rng.gen_range(length))
+        .map(|_| *alphabet.choose(rng).unwrap() as char)
+        .collect()
+}
+
+//A word that is not in used yet, which is then added to it.
+//Once most words of the given lengths are taken, longer ones are tried, so this never runs out.
+fn unique_word(
+    rng: &mut StdRng,
+    used: &mut HashSet<String>,
+    alphabet: &[u8],
+    mut length: RangeInclusive<usize>,
+) -> String {
+    loop {
+        for _ in 0..20 {
+            let word = random_word(rng, alphabet, length.clone());
+            if used.insert(word.clone()) {
+                return word;
+            }
+        }
+        length = length.start() + 1..=length.end() + 1;
+    }
+}
+
+//a grid where every cell is picked from the choices, weighted by how often they should appear
+fn random_grid(rng: &mut StdRng, size: usize, choices: &[(char, u32)]) -> String {
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| {
+                    choices
+                        .choose_weighted(rng, |(_, weight)| *weight)
+                        .unwrap()
+                        .0
+                })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+//size lines of letters, digits and spelled out digits, with at least one digit in each line
+fn day1(rng: &mut StdRng, size: usize) -> String {
+    const WORDS: [&str; 9] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    ];
+    (0..size)
+        .map(|_| {
+            let mut pieces = (0..rng.gen_range(1..=6))
+                .map(|_| match rng.gen_range(0..3) {
+                    0 => rng.gen_range(1..=9).to_string(),
+                    1 => WORDS.choose(rng).unwrap().to_string(),
+                    _ => random_word(rng, LOWERCASE, 1..=5),
+                })
+                .collect_vec();
+            let digit = rng.gen_range(1..=9).to_string();
+            pieces.insert(rng.gen_range(0..=pieces.len()), digit);
+            pieces.concat()
+        })
+        .join("\n")
+}
+
+//size games
+fn day2(rng: &mut StdRng, size: usize) -> String {
+    (1..=size)
+        .map(|id| {
+            let samples = (0..rng.gen_range(1..=6))
+                .map(|_| {
+                    let mut colors = vec!["red", "green", "blue"];
+                    colors.shuffle(rng);
+                    colors.truncate(rng.gen_range(1..=3));
+                    colors
+                        .into_iter()
+                        .map(|color| format!("{} {}", rng.gen_range(1..=20), color))
+                        .join(", ")
+                })
+                .join("; ");
+            format!("Game {}: {}", id, samples)
+        })
+        .join("\n")
+}
+
+//a size by size schematic
+fn day3(rng: &mut StdRng, size: usize) -> String {
+    const SYMBOLS: &[u8] = b"*#+$/@=%&-";
+    (0..size)
+        .map(|_| {
+            let mut row = vec!['.'; size];
+            let mut col = 0;
+            while col < size {
+                let length = rng.gen_range(1..=3);
+                if col + length <= size && rng.gen_bool(0.15) {
+                    let number =
+                        rng.gen_range(10_u32.pow(length as u32 - 1)..10_u32.pow(length as u32));
+                    for (i, digit) in number.to_string().chars().enumerate() {
+                        row[col + i] = digit;
+                    }
+                    //leave a gap so the next number is not glued on
+                    col += length + 1;
+                } else {
+                    if rng.gen_bool(0.08) {
+                        row[col] = *SYMBOLS.choose(rng).unwrap() as char;
+                    }
+                    col += 1;
+                }
+            }
+            row.into_iter().collect::<String>()
+        })
+        .join("\n")
+}
+
+//size cards
+fn day4(rng: &mut StdRng, size: usize) -> String {
+    //every card wins copies of the ones after it, so keep the total from overflowing
+    const MAX_CARDS: u64 = 10_000_000;
+
+    let mut counts = vec![1_u64; size];
+    let matches = (0..size)
+        .map(|i| {
+            let mut matches = rng.gen_range(0..=10).min(size - i - 1);
+            while matches > 0 && counts.iter().sum::<u64>() + counts[i] * matches as u64 > MAX_CARDS
+            {
+                matches -= 1;
+            }
+            let copies = counts[i];
+            for count in &mut counts[i + 1..=i + matches] {
+                *count += copies;
+            }
+            matches
+        })
+        .collect_vec();
+
+    matches
+        .into_iter()
+        .enumerate()
+        .map(|(i, matches)| {
+            let mut numbers = (1..100).collect_vec();
+            numbers.shuffle(rng);
+            let (winning, others) = numbers.split_at(10);
+            let mut mine = winning[..matches]
+                .iter()
+                .chain(&others[..25 - matches])
+                .collect_vec();
+            mine.shuffle(rng);
+
+            format!(
+                "Card {:>3}: {} | {}",
+                i + 1,
+                winning.iter().map(|num| format!("{:>2}", num)).join(" "),
+                mine.into_iter().map(|num| format!("{:>2}", num)).join(" ")
+            )
+        })
+        .join("\n")
+}
+
+//an almanac where each map has size ranges, and at least one
+fn day5(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(1);
+    const MAX: u64 = 1 << 32;
+    const MAPS: [&str; 7] = [
+        "seed-to-soil",
+        "soil-to-fertilizer",
+        "fertilizer-to-water",
+        "water-to-light",
+        "light-to-temperature",
+        "temperature-to-humidity",
+        "humidity-to-location",
+    ];
+
+    //like the real inputs, the ranges are far too long to try every seed
+    let seeds = (0..10)
+        .flat_map(|_| {
+            let length = rng.gen_range(1..MAX / 16);
+            [rng.gen_range(0..MAX - length), length]
+        })
+        .join(" ");
+
+    let maps = MAPS
+        .into_iter()
+        .map(|name| {
+            //pairs of cut points are the non-overlapping source ranges
+            let cuts = iter::repeat_with(|| rng.gen_range(0..MAX))
+                .take(2 * size)
+                .sorted()
+                .dedup()
+                .collect_vec();
+            let mut ranges = cuts
+                .chunks_exact(2)
+                .map(|pair| {
+                    let (source, length) = (pair[0], pair[1] - pair[0]);
+                    let dest = rng.gen_range(0..MAX - length);
+                    format!("{} {} {}", dest, source, length)
+                })
+                .collect_vec();
+            ranges.shuffle(rng);
+            format!("{} map:\n{}", name, ranges.join("\n"))
+        })
+        .join("\n\n");
+
+    format!("seeds: {}\n\n{}", seeds, maps)
+}
+
+//size races, at most 4 since part 2 joins them all into one long race
+fn day6(rng: &mut StdRng, size: usize) -> String {
+    let races = (0..size.clamp(1, 4))
+        .map(|_| {
+            let time = rng.gen_range(7..100_u64);
+            let record = (time / 2) * (time - time / 2);
+            (time, rng.gen_range(0..record))
+        })
+        .collect_vec();
+    format!(
+        "Time:    {}\nDistance:{}",
+        races
+            .iter()
+            .map(|(time, _)| format!("{:>5}", time))
+            .join(""),
+        races
+            .iter()
+            .map(|(_, dist)| format!("{:>5}", dist))
+            .join("")
+    )
+}
+
+//size hands
+fn day7(rng: &mut StdRng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{} {}",
+                random_word(rng, b"23456789TJQKA", 5..=5),
+                rng.gen_range(1..=1000)
+            )
+        })
+        .join("\n")
+}
+
+//Six ghosts that each loop through about size nodes.
+//Every loop passes its Z node exactly when the steps start over, like in the real input.
+//every ghost's nodes get their own 3 letter name, and there are only so many of those
+const DAY8_MAX_SIZE: usize = 1500;
+
+fn day8(rng: &mut StdRng, size: usize) -> String {
+    let steps = random_word(rng, b"LR", 5..=20);
+    let size = size.min(DAY8_MAX_SIZE);
+
+    let mut used = HashSet::new();
+    let mut name = |rng: &mut StdRng, last: &[u8]| loop {
+        let name =
+            random_word(rng, b"BCDEFGHIJKLMNOPQRSTUVWXY", 2..=2) + &random_word(rng, last, 1..=1);
+        if used.insert(name.clone()) {
+            return name;
+        }
+    };
+
+    let mut lines = Vec::new();
+    for ghost in 0..6 {
+        let (start, end) = match ghost {
+            0 => ("AAA".to_string(), "ZZZ".to_string()),
+            _ => (name(rng, b"A"), name(rng, b"Z")),
+        };
+        let length = steps.len() * rng.gen_range(1..=(size / steps.len()).max(1));
+        let nodes = iter::once(end)
+            .chain((1..length).map(|_| name(rng, b"BCDEFGHIJKLMNOPQRSTUVWXY")))
+            .collect_vec();
+
+        //a wrong turn leads back to the same node, which is never taken anyway
+        let edges = |from: &str, i: usize| {
+            let next = &nodes[(i + 1) % length];
+            match steps.as_bytes()[i % steps.len()] {
+                b'L' => format!("{} = ({}, {})", from, next, from),
+                _ => format!("{} = ({}, {})", from, from, next),
+            }
+        };
+        lines.push(edges(&start, 0));
+        for (i, node) in nodes.iter().enumerate() {
+            lines.push(edges(node, i));
+        }
+    }
+    lines.shuffle(rng);
+
+    format!("{}\n\n{}", steps, lines.join("\n"))
+}
+
+//size polynomial sequences
+fn day9(rng: &mut StdRng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let coefficients = (0..=rng.gen_range(0..=5))
+                .map(|_| rng.gen_range(-3..=3))
+                .collect_vec();
+            (0..21)
+                .map(|x: i32| {
+                    coefficients
+                        .iter()
+                        .rev()
+                        .fold(0, |acc, coefficient| acc * x + coefficient)
+                })
+                .join(" ")
+        })
+        .join("\n")
+}
+
+//Column ranges for each of size rows, where each row overlaps the one before it.
+//The union has no holes and no cells touching only at a corner, so its outline is a simple loop.
+fn random_shape(rng: &mut StdRng, size: usize) -> Vec<Range<usize>> {
+    let mut previous: Option<Range<usize>> = None;
+    (0..size)
+        .map(|_| {
+            let range = loop {
+                let start = rng.gen_range(0..size);
+                let range = start..rng.gen_range(start + 1..=size);
+                match &previous {
+                    Some(previous)
+                        if range.start.max(previous.start) >= range.end.min(previous.end) => {}
+                    _ => break range,
+                }
+            };
+            previous = Some(range.clone());
+            range
+        })
+        .collect()
+}
+
+//the corners of the shape's outline as (x, y), going clockwise
+fn outline(shape: &[Range<usize>]) -> Vec<(usize, usize)> {
+    let right_side = shape
+        .iter()
+        .enumerate()
+        .flat_map(|(y, range)| [(range.end, y), (range.end, y + 1)]);
+    let left_side = shape
+        .iter()
+        .enumerate()
+        .rev()
+        .flat_map(|(y, range)| [(range.start, y + 1), (range.start, y)]);
+
+    let points = right_side.chain(left_side).dedup().collect_vec();
+    //only keep the points where the outline turns
+    points
+        .iter()
+        .circular_tuple_windows()
+        .filter(|&(previous, point, next)| {
+            !(previous.0 == point.0 && point.0 == next.0
+                || previous.1 == point.1 && point.1 == next.1)
+        })
+        .map(|(_, &point, _)| point)
+        .collect()
+}
+
+//the outline of a random shape, drawn at double scale so there is room inside the loop
+fn day10(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(1);
+    let corners = outline(&random_shape(rng, size));
+
+    //every cell of the loop in order, as (row, col)
+    let path = corners
+        .iter()
+        .circular_tuple_windows()
+        .flat_map(|(&(x1, y1), &(x2, y2))| {
+            let length = x1.abs_diff(x2) + y1.abs_diff(y2);
+            (0..2 * length).map(move |i| {
+                let along = |from: usize, to: usize| {
+                    if to >= from {
+                        2 * from + i
+                    } else {
+                        2 * from - i
+                    }
+                };
+                match x1 == x2 {
+                    true => (along(y1, y2), 2 * x1),
+                    false => (2 * y1, along(x1, x2)),
+                }
+            })
+        })
+        .collect_vec();
+
+    let side = 2 * size + 1;
+    let mut grid = (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| *b".......|-LJ7F".choose(rng).unwrap() as char)
+                .collect_vec()
+        })
+        .collect_vec();
+
+    for (previous, &(row, col), next) in path.iter().circular_tuple_windows() {
+        let mut connections = [previous, next].map(|&(other_row, other_col)| {
+            match (other_row.cmp(&row), other_col.cmp(&col)) {
+                (Ordering::Less, _) => 'N',
+                (Ordering::Greater, _) => 'S',
+                (_, Ordering::Less) => 'W',
+                _ => 'E',
+            }
+        });
+        connections.sort();
+        grid[row][col] = match connections {
+            ['N', 'S'] => '|',
+            ['E', 'W'] => '-',
+            ['E', 'N'] => 'L',
+            ['N', 'W'] => 'J',
+            ['S', 'W'] => '7',
+            _ => 'F',
+        };
+    }
+
+    //only the two pipes on the loop may connect to the start
+    let start @ (row, col) = *path.choose(rng).unwrap();
+    grid[row][col] = 'S';
+    let neighbors = [
+        (row.wrapping_sub(1), col),
+        (row + 1, col),
+        (row, col.wrapping_sub(1)),
+        (row, col + 1),
+    ];
+    let path = path.into_iter().collect::<HashSet<_>>();
+    for (row, col) in neighbors {
+        if row < side && col < side && !path.contains(&(row, col)) && (row, col) != start {
+            grid[row][col] = '.';
+        }
+    }
+
+    grid.into_iter()
+        .map(|row| row.into_iter().collect::<String>())
+        .join("\n")
+}
+
+//a size by size image
+fn day11(rng: &mut StdRng, size: usize) -> String {
+    random_grid(rng, size, &[('.', 30), ('#', 1)])
+}
+
+//size rows of springs, made by hiding parts of a valid arrangement
+fn day12(rng: &mut StdRng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let groups = (0..rng.gen_range(1..=5))
+                .map(|_| rng.gen_range(1..=4))
+                .collect_vec();
+            let springs = groups
+                .iter()
+                .map(|&group| ".".repeat(rng.gen_range(1..=2)) + &"#".repeat(group))
+                .collect::<String>()
+                + &".".repeat(rng.gen_range(0..=2));
+            let springs = springs
+                .strip_prefix('.')
+                .filter(|_| rng.gen_bool(0.5))
+                .unwrap_or(&springs)
+                .chars()
+                .map(|c| if rng.gen_bool(0.5) { '?' } else { c })
+                .collect::<String>();
+            format!("{} {}", springs, groups.iter().join(","))
+        })
+        .join("\n")
+}
+
+//how many cells differ when reflecting the rows across the vertical line before column i
+fn reflection_differences(rows: &[Vec<char>], i: usize) -> usize {
+    rows.iter()
+        .map(|row| {
+            row[..i]
+                .iter()
+                .rev()
+                .zip(&row[i..])
+                .filter(|(left, right)| left != right)
+                .count()
+        })
+        .sum()
+}
+
+fn transpose(rows: &[Vec<char>]) -> Vec<Vec<char>> {
+    (0..rows[0].len())
+        .map(|col| rows.iter().map(|row| row[col]).collect())
+        .collect()
+}
+
+//A pattern with exactly one line of reflection, and exactly one other line that is off by one cell.
+//The cells mirrored across both lines are tied together, except for one pair across the second.
+fn day13_pattern(rng: &mut StdRng) -> Vec<Vec<char>> {
+    fn find(parents: &mut [usize], i: usize) -> usize {
+        if parents[i] != i {
+            parents[i] = find(parents, parents[i]);
+        }
+        parents[i]
+    }
+
+    loop {
+        let row_count = rng.gen_range(5..=15);
+        let col_count = rng.gen_range(5..=15);
+        let reflection = rng.gen_range(1..col_count);
+        let smudged = rng.gen_range(1..col_count);
+        if reflection == smudged {
+            continue;
+        }
+
+        let mirrored =
+            |line: usize| (0..line.min(col_count - line)).map(move |k| (line - 1 - k, line + k));
+        let smudge = *mirrored(smudged).collect_vec().choose(rng).unwrap();
+        let smudge_row = rng.gen_range(0..row_count);
+
+        let classes = |skip: Option<(usize, usize)>| {
+            let mut parents = (0..col_count).collect_vec();
+            for (a, b) in mirrored(reflection).chain(mirrored(smudged)) {
+                if Some((a, b)) != skip {
+                    let (a, b) = (find(&mut parents, a), find(&mut parents, b));
+                    parents[a] = b;
+                }
+            }
+            (0..col_count).map(|i| find(&mut parents, i)).collect_vec()
+        };
+        let tied = classes(None);
+        let smudge_classes = classes(Some(smudge));
+        if smudge_classes[smudge.0] == smudge_classes[smudge.1] {
+            continue;
+        }
+
+        let rows = (0..row_count)
+            .map(|row| {
+                let classes = if row == smudge_row {
+                    &smudge_classes
+                } else {
+                    &tied
+                };
+                let mut values = (0..col_count).map(|_| rng.gen_bool(0.5)).collect_vec();
+                if row == smudge_row {
+                    values[classes[smudge.1]] = !values[classes[smudge.0]];
+                }
+                classes
+                    .iter()
+                    .map(|&class| if values[class] { '#' } else { '.' })
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        //the random rows can still line up by chance, so check every line
+        let rows = if rng.gen_bool(0.5) {
+            transpose(&rows)
+        } else {
+            rows
+        };
+        let differences = |rows: &[Vec<char>]| {
+            (1..rows[0].len())
+                .map(|i| reflection_differences(rows, i))
+                .collect_vec()
+        };
+        let all = chain!(differences(&rows), differences(&transpose(&rows))).collect_vec();
+        if all.iter().filter(|&&d| d == 0).count() == 1
+            && all.iter().filter(|&&d| d == 1).count() == 1
+        {
+            return rows;
+        }
+    }
+}
+
+//size patterns, and at least one
+fn day13(rng: &mut StdRng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            day13_pattern(rng)
+                .into_iter()
+                .map(|row| row.into_iter().collect::<String>())
+                .join("\n")
+        })
+        .join("\n\n")
+}
+
+//a size by size platform
+fn day14(rng: &mut StdRng, size: usize) -> String {
+    random_grid(rng, size, &[('.', 14), ('O', 4), ('#', 2)])
+}
+
+//size steps, reusing labels so lenses get replaced and removed
+fn day15(rng: &mut StdRng, size: usize) -> String {
+    let labels = (0..size / 4 + 1)
+        .map(|_| random_word(rng, LOWERCASE, 2..=6))
+        .collect_vec();
+    (0..size)
+        .map(|_| {
+            let label = labels.choose(rng).unwrap();
+            match rng.gen_bool(0.3) {
+                true => format!("{}-", label),
+                false => format!("{}={}", label, rng.gen_range(1..=9)),
+            }
+        })
+        .join(",")
+}
+
+//a size by size contraption, at least 1 wide so the beam has somewhere to enter
+fn day16(rng: &mut StdRng, size: usize) -> String {
+    random_grid(
+        rng,
+        size.max(1),
+        &[('.', 40), ('/', 2), ('\\', 2), ('|', 2), ('-', 2)],
+    )
+}
+
+//a size by size city, at least 5 wide so the ultra crucible can reach the end
+fn day17(rng: &mut StdRng, size: usize) -> String {
+    let digits = ('1'..='9').map(|digit| (digit, 1)).collect_vec();
+    random_grid(rng, size.max(5), &digits)
+}
+
+//the outline of a random shape, with each row and column stretched by a random amount
+fn day18_outline(rng: &mut StdRng, size: usize, widths: Range<usize>) -> Vec<(char, usize)> {
+    let corners = outline(&random_shape(rng, size));
+    let offset = |rng: &mut StdRng| {
+        iter::once(0)
+            .chain((0..size).scan(0, |total, _| {
+                *total += rng.gen_range(widths.clone());
+                Some(*total)
+            }))
+            .collect_vec()
+    };
+    let (xs, ys) = (offset(rng), offset(rng));
+
+    corners
+        .iter()
+        .circular_tuple_windows()
+        .map(|(&(x1, y1), &(x2, y2))| match (x1.cmp(&x2), y1.cmp(&y2)) {
+            (Ordering::Less, _) => ('R', xs[x2] - xs[x1]),
+            (Ordering::Greater, _) => ('L', xs[x1] - xs[x2]),
+            (_, Ordering::Less) => ('D', ys[y2] - ys[y1]),
+            _ => ('U', ys[y1] - ys[y2]),
+        })
+        .collect()
+}
+
+//A dig plan around a shape with about 4 * size corners.
+//The colors hold a second, much larger shape with the same number of steps.
+fn day18(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(1);
+    let mut plan = day18_outline(rng, size, 2..11);
+    //the distances have to fit in 5 hex digits
+    let mut colors = day18_outline(rng, size, 2..(0xfffff / size).max(3));
+
+    //split the longest steps of the shorter plan in two until both have the same length
+    while plan.len() != colors.len() {
+        let shorter = if plan.len() < colors.len() {
+            &mut plan
+        } else {
+            &mut colors
+        };
+        let (i, &(direction, distance)) = shorter
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, (_, distance))| *distance)
+            .unwrap();
+        let split = rng.gen_range(1..distance);
+        shorter[i] = (direction, split);
+        shorter.insert(i + 1, (direction, distance - split));
+    }
+
+    plan.into_iter()
+        .zip(colors)
+        .map(
+            |((direction, distance), (color_direction, color_distance))| {
+                let digit = match color_direction {
+                    'R' => 0,
+                    'D' => 1,
+                    'L' => 2,
+                    _ => 3,
+                };
+                format!(
+                    "{} {} (#{:05x}{})",
+                    direction, distance, color_distance, digit
+                )
+            },
+        )
+        .join("\n")
+}
+
+//size workflows forming a tree under "in", and size parts
+fn day19(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(1);
+    let mut used = HashSet::from(["in".to_string()]);
+    let names = iter::once("in".to_string())
+        .chain((1..size).map(|_| unique_word(rng, &mut used, LOWERCASE, 2..=3)))
+        .collect_vec();
+
+    //each workflow sends parts to the ones after it, so there are no cycles
+    let mut targets = vec![Vec::new(); size];
+    for (child, name) in names.iter().enumerate().skip(1) {
+        let parent = rng.gen_range(0..child);
+        targets[parent].push(name.clone());
+    }
+
+    let mut workflows = targets
+        .into_iter()
+        .zip(&names)
+        .map(|(mut targets, name)| {
+            let rule_count = rng.gen_range(2..=4).max(targets.len() + 1);
+            while targets.len() < rule_count {
+                targets.push(["A", "R"].choose(rng).unwrap().to_string());
+            }
+            targets.shuffle(rng);
+            let fallback = targets.pop().unwrap();
+            let rules = targets
+                .into_iter()
+                .map(|target| {
+                    format!(
+                        "{}{}{}:{}",
+                        *b"xmas".choose(rng).unwrap() as char,
+                        *b"<>".choose(rng).unwrap() as char,
+                        rng.gen_range(1..=4000),
+                        target
+                    )
+                })
+                .chain(iter::once(fallback))
+                .join(",");
+            format!("{}{{{}}}", name, rules)
+        })
+        .collect_vec();
+    workflows.shuffle(rng);
+
+    let parts = (0..size)
+        .map(|_| {
+            let [x, m, a, s] = [(); 4].map(|_| rng.gen_range(1..=4000));
+            format!("{{x={},m={},a={},s={}}}", x, m, a, s)
+        })
+        .join("\n");
+
+    format!("{}\n\n{}", workflows.join("\n"), parts)
+}
+
+//Like the real inputs, size 12 bit counters that each reset after a random number of presses,
+//joined by inverters into the conjunction feeding rx. There are at most 5 so the answer fits in a u64.
+fn day20(rng: &mut StdRng, size: usize) -> String {
+    let mut used = HashSet::from(["rx".to_string(), "broadcaster".to_string()]);
+    let mut name = |rng: &mut StdRng| {
+        iter::repeat_with(|| random_word(rng, LOWERCASE, 2..=3))
+            .find(|name| used.insert(name.clone()))
+            .unwrap()
+    };
+
+    let join = name(rng);
+    let mut starts = Vec::new();
+    let mut lines = Vec::new();
+    for _ in 0..size.clamp(1, 5) {
+        //the first flip-flop always feeds the conjunction, so the period is odd
+        let period = rng.gen_range(2048..4096) | 1;
+        let flip_flops = (0..12).map(|_| name(rng)).collect_vec();
+        let (conjunction, inverter) = (name(rng), name(rng));
+
+        for (bit, flip_flop) in flip_flops.iter().enumerate() {
+            let mut destinations = chain!(
+                flip_flops.get(bit + 1),
+                (period >> bit & 1 == 1).then_some(&conjunction)
+            );
+            lines.push(format!("%{} -> {}", flip_flop, destinations.join(", ")));
+        }
+        //resetting adds the missing bits and one more, which overflows back to 0
+        let resets = flip_flops
+            .iter()
+            .enumerate()
+            .filter(|&(bit, _)| bit == 0 || period >> bit & 1 == 0)
+            .map(|(_, flip_flop)| flip_flop);
+        lines.push(format!(
+            "&{} -> {}",
+            conjunction,
+            chain!(resets, [&inverter]).join(", ")
+        ));
+        lines.push(format!("&{} -> {}", inverter, join));
+        starts.push(flip_flops[0].clone());
+    }
+    lines.push(format!("broadcaster -> {}", starts.join(", ")));
+    lines.push(format!("&{} -> rx", join));
+    lines.shuffle(rng);
+    lines.join("\n")
+}
+
+//Like the real inputs, an odd sized square garden with the start in the middle,
+//and no rocks on the middle row and column or around the edge
+fn day21(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(1) | 1;
+    let (middle, last) = (size / 2, size - 1);
+    (0..size)
+        .map(|row| {
+            (0..size)
+                .map(|col| {
+                    if (row, col) == (middle, middle) {
+                        'S'
+                    } else if [row, col]
+                        .iter()
+                        .any(|&i| i == 0 || i == middle || i == last)
+                    {
+                        '.'
+                    } else if rng.gen_bool(0.15) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+//size bricks up to 4 cubes long over a 10 by 10 area, each starting above the last so none overlap
+fn day22(rng: &mut StdRng, size: usize) -> String {
+    let mut bottom = 1;
+    let mut bricks = (0..size)
+        .map(|_| {
+            let length = rng.gen_range(0..4);
+            let axis = rng.gen_range(0..3);
+            let mut start = [rng.gen_range(0..10), rng.gen_range(0..10), bottom];
+            if axis < 2 {
+                start[axis] = start[axis].min(9 - length);
+            }
+            let mut end = start;
+            end[axis] += length;
+            bottom = end[2] + 1;
+            format!(
+                "{},{},{}~{},{},{}",
+                start[0], start[1], start[2], end[0], end[1], end[2]
+            )
+        })
+        .collect_vec();
+    bricks.shuffle(rng);
+    bricks.join("\n")
+}
+
+//Like the real inputs, a size by size lattice of places where the trail can split, at most 6 by 6.
+//Neighboring places are joined by straight trails, most of them with slopes leading south or east.
+fn day23(rng: &mut StdRng, size: usize) -> String {
+    const SPACING: usize = 4;
+    let lattice = size.clamp(1, 6);
+    let place = |i: usize| 2 + SPACING * i;
+    let col = |j: usize| 1 + SPACING * j;
+    let (rows, cols) = (place(lattice - 1) + 3, col(lattice - 1) + 2);
+    let mut grid = vec![vec!['#'; cols]; rows];
+
+    //into the first place and out of the last
+    for row in 0..2 {
+        grid[row][col(0)] = '.';
+        grid[rows - 1 - row][col(lattice - 1)] = '.';
+    }
+
+    //trails south and east from every place, always keeping one to reach the last row and column
+    for (i, j) in (0..lattice).cartesian_product(0..lattice) {
+        grid[place(i)][col(j)] = '.';
+        let (row, column) = (place(i), col(j));
+        if i + 1 < lattice && (j + 1 == lattice || rng.gen_bool(0.8)) {
+            for step in 1..SPACING {
+                grid[row + step][column] = '.';
+            }
+            if rng.gen_bool(0.9) {
+                grid[row + 1][column] = 'v';
+                grid[row + SPACING - 1][column] = 'v';
+            }
+        }
+        if j + 1 < lattice && (i + 1 == lattice || rng.gen_bool(0.8)) {
+            for step in 1..SPACING {
+                grid[row][column + step] = '.';
+            }
+            if rng.gen_bool(0.9) {
+                grid[row][column + 1] = '>';
+                grid[row][column + SPACING - 1] = '>';
+            }
+        }
+    }
+
+    grid.into_iter()
+        .map(|row| row.into_iter().collect::<String>())
+        .join("\n")
+}
+
+//size hailstones, at least 4, that a single throw of a rock hits at different times.
+//Like the real inputs, positions have 15 digits and velocities are a few hundred at most.
+fn day24(rng: &mut StdRng, size: usize) -> String {
+    let vector =
+        |rng: &mut StdRng, range: Range<i64>| [(); 3].map(|_| rng.gen_range(range.clone()));
+    let rock = vector(rng, 200_000_000_000_000..400_000_000_000_000);
+    let rock_velocity = vector(rng, -300..300);
+
+    let mut times = HashSet::new();
+    (0..size.max(4))
+        .map(|_| {
+            let time = iter::repeat_with(|| rng.gen_range(1..1_000_000_000_000_i64))
+                .find(|&time| times.insert(time))
+                .unwrap();
+            let velocity = vector(rng, -300..300);
+            let position = [0, 1, 2].map(|i| rock[i] + time * (rock_velocity[i] - velocity[i]));
+            format!(
+                "{}, {}, {} @ {}, {}, {}",
+                position[0], position[1], position[2], velocity[0], velocity[1], velocity[2]
+            )
+        })
+        .join("\n")
+}
+
+//Two groups of about size / 2 components joined by 3 wires.
+//Each group is a ring where every component is also wired to the next but one, plus some random wires,
+//so splitting a group takes at least 4 wires.
+fn day25(rng: &mut StdRng, size: usize) -> String {
+    //like the real inputs the names have 3 letters, until there are too many components for that
+    let mut used = HashSet::new();
+    let mut name = |rng: &mut StdRng| unique_word(rng, &mut used, LOWERCASE, 3..=3);
+
+    let groups =
+        [size / 2, size - size / 2].map(|len| (0..len.max(5)).map(|_| name(rng)).collect_vec());
+    let mut wires = Vec::new();
+    for group in &groups {
+        let len = group.len();
+        for i in 0..len {
+            wires.push((&group[i], &group[(i + 1) % len]));
+            wires.push((&group[i], &group[(i + 2) % len]));
+        }
+        for _ in 0..len / 4 {
+            let (a, b) = (group.choose(rng).unwrap(), group.choose(rng).unwrap());
+            if a != b {
+                wires.push((a, b));
+            }
+        }
+    }
+    for _ in 0..3 {
+        wires.push((
+            groups[0].choose(rng).unwrap(),
+            groups[1].choose(rng).unwrap(),
+        ));
+    }
+
+    //each wire is listed once, under either of its components
+    let mut listed = HashMap::<&String, Vec<&String>>::new();
+    for (a, b) in wires {
+        let (a, b) = if rng.gen() { (a, b) } else { (b, a) };
+        listed.entry(a).or_default().push(b);
+    }
+    let mut lines = listed
+        .into_iter()
+        .sorted()
+        .map(|(component, others)| format!("{}: {}", component, others.iter().join(" ")))
+        .collect_vec();
+    lines.shuffle(rng);
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    //every generated input should be solvable, whatever the size or seed
+    #[test]
+    fn generated_inputs_are_valid() {
+        for generator in GENERATORS {
+            let day = registry::get(generator.day).unwrap();
+            for (size, seed) in [(0, 4), (1, 0), (5, 1), (12, 2), (12, 3)] {
+                let input = generator.generate(size, seed);
+                for part in day.parts() {
+                    if let Err(e) = day.solve(part, &input) {
+                        panic!(
+                            "Day {} part {} failed on size {} seed {}: {:#}\n{}",
+                            generator.day,
+                            part.number(),
+                            size,
+                            seed,
+                            e,
+                            input
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    //sizes far past a real input, where short names run out
+    #[test]
+    fn large_sizes_finish() {
+        for day in [8, 19, 25] {
+            let input = get(day).unwrap().generate(20_000, 0);
+            assert!(!input.is_empty());
+        }
+    }
+
+    #[test]
+    fn same_seed_same_input() {
+        for generator in GENERATORS {
+            assert_eq!(generator.generate(10, 7), generator.generate(10, 7));
+        }
+    }
+}
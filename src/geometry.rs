@@ -0,0 +1,242 @@
+use crate::grid::{Offset, Position};
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+//Compass directions on a grid where north is up (decreasing row)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+//Relative turns, e.g. the left/right instructions of day 8
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter()
+    }
+
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Self::North => Self::West,
+            Self::East => Self::North,
+            Self::South => Self::East,
+            Self::West => Self::South,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::North => Self::East,
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::East => Self::West,
+            Self::South => Self::North,
+            Self::West => Self::East,
+        }
+    }
+
+    //(row offset, column offset) of a single step, for use with Grid::step
+    pub fn offset(self) -> Offset {
+        match self {
+            Self::North => (-1, 0),
+            Self::East => (0, 1),
+            Self::South => (1, 0),
+            Self::West => (0, -1),
+        }
+    }
+
+    //a single step as a Point, where y grows southward like rows do
+    pub fn unit(self) -> Point {
+        let (y, x) = self.offset();
+        Point { x, y }
+    }
+
+    //accepts both U/D/L/R and N/E/S/W
+    pub fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            'U' | 'N' => Some(Self::North),
+            'R' | 'E' => Some(Self::East),
+            'D' | 'S' => Some(Self::South),
+            'L' | 'W' => Some(Self::West),
+            _ => None,
+        }
+    }
+
+    //the slopes of day 23
+    pub fn from_arrow(arrow: char) -> Option<Self> {
+        match arrow {
+            '^' => Some(Self::North),
+            '>' => Some(Self::East),
+            'v' => Some(Self::South),
+            '<' => Some(Self::West),
+            _ => None,
+        }
+    }
+
+    //the digit encoding used by the hex colors of day 18
+    pub fn from_digit(digit: u32) -> Option<Self> {
+        match digit {
+            0 => Some(Self::East),
+            1 => Some(Self::South),
+            2 => Some(Self::West),
+            3 => Some(Self::North),
+            _ => None,
+        }
+    }
+}
+
+impl Turn {
+    pub fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            'L' => Some(Self::Left),
+            'R' => Some(Self::Right),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, PartialOrd, Ord)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub fn new(x: isize, y: isize) -> Self {
+        Point { x, y }
+    }
+
+    pub fn manhattan_distance(self, other: Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.unit()
+    }
+
+    //the grid position with row = y and column = x, if both are non-negative
+    pub fn to_position(self) -> Option<Position> {
+        Some((self.y.try_into().ok()?, self.x.try_into().ok()?))
+    }
+}
+
+impl From<Position> for Point {
+    fn from((row, col): Position) -> Self {
+        Point {
+            x: col as isize,
+            y: row as isize,
+        }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, scale: isize) -> Point {
+        Point {
+            x: self.x * scale,
+            y: self.y * scale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        for direction in Direction::iter() {
+            assert_eq!(direction.turn(Turn::Left).turn(Turn::Right), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.reverse());
+            assert_eq!(direction.turn_left().turn_left(), direction.reverse());
+            assert_eq!(direction.reverse().reverse(), direction);
+            assert_ne!(direction.reverse(), direction);
+        }
+        assert_eq!(Direction::North.turn(Turn::Right), Direction::East);
+        assert_eq!(Direction::North.turn(Turn::Left), Direction::West);
+        assert_eq!(Direction::East.reverse(), Direction::West);
+    }
+
+    #[test]
+    fn letters() {
+        assert_eq!(Turn::from_letter('L'), Some(Turn::Left));
+        assert_eq!(Turn::from_letter('R'), Some(Turn::Right));
+        assert_eq!(Turn::from_letter('l'), None);
+        assert_eq!(Turn::from_letter('U'), None);
+        assert_eq!(Direction::from_letter('U'), Some(Direction::North));
+        assert_eq!(Direction::from_letter('W'), Some(Direction::West));
+        assert_eq!(Direction::from_letter('X'), None);
+    }
+
+    #[test]
+    fn steps() {
+        let origin = Point::default();
+        assert_eq!(origin.step(Direction::North), Point::new(0, -1));
+        assert_eq!(origin.step(Direction::East), Point::new(1, 0));
+        assert_eq!(origin.step(Direction::South), Point::new(0, 1));
+        assert_eq!(origin.step(Direction::West), Point::new(-1, 0));
+        let point = Point::new(3, 5);
+        assert_eq!(point.step(Direction::South).to_position(), Some((6, 3)));
+        assert_eq!(Point::from((6, 3)), Point::new(3, 6));
+    }
+
+    #[test]
+    fn negative_positions() {
+        assert_eq!(Point::new(0, 0).to_position(), Some((0, 0)));
+        assert_eq!(Point::new(0, 0).step(Direction::North).to_position(), None);
+        assert_eq!(Point::new(0, 0).step(Direction::West).to_position(), None);
+        assert_eq!(Point::new(-1, -1).to_position(), None);
+    }
+}
@@ -0,0 +1,378 @@
+use crate::{error, geometry::Direction};
+use itertools::Itertools;
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+//(row, column)
+pub type Position = (usize, usize);
+
+//(row offset, column offset)
+pub type Offset = (isize, isize);
+
+const ORTHOGONAL: [Offset; 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+const SURROUNDING: [Offset; 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErrorKind {
+    #[error("Unexpected character '{0}'")]
+    UnexpectedCharacter(char),
+    #[error("Row has {found} columns, expected {expected}")]
+    RaggedRow { expected: usize, found: usize },
+    #[error("Expected {expected} cells, found {found}")]
+    WrongCellCount { expected: usize, found: usize },
+}
+
+pub type Error = error::Error<ErrorKind>;
+
+//A rectangular grid stored row by row in a single Vec
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    row_count: usize,
+    col_count: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(row_count: usize, col_count: usize, cells: Vec<T>) -> Result<Self, ErrorKind> {
+        if cells.len() != row_count * col_count {
+            return Err(ErrorKind::WrongCellCount {
+                expected: row_count * col_count,
+                found: cells.len(),
+            });
+        }
+        Ok(Grid {
+            cells,
+            row_count,
+            col_count,
+        })
+    }
+
+    //errors point at the first cell past the end of the shorter row
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Result<Self, Error> {
+        let mut cells = Vec::new();
+        let mut row_count = 0;
+        let mut col_count = 0;
+        for (i, row) in rows.into_iter().enumerate() {
+            if i == 0 {
+                col_count = row.len();
+            } else if row.len() != col_count {
+                let kind = ErrorKind::RaggedRow {
+                    expected: col_count,
+                    found: row.len(),
+                };
+                return Err(Error::new((i, row.len().min(col_count)), kind));
+            }
+            cells.extend(row);
+            row_count += 1;
+        }
+        Ok(Grid {
+            cells,
+            row_count,
+            col_count,
+        })
+    }
+
+    //Parses one cell per character, one row per line.
+    //parse_cell returns None for characters that are not allowed in the grid.
+    pub fn parse(
+        input: &str,
+        mut parse_cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, Error> {
+        let rows = input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(j, c)| {
+                        parse_cell(c)
+                            .ok_or_else(|| Error::new((i, j), ErrorKind::UnexpectedCharacter(c)))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Grid::from_rows(rows)
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    pub fn col_count(&self) -> usize {
+        self.col_count
+    }
+
+    pub fn contains(&self, (row, col): Position) -> bool {
+        row < self.row_count && col < self.col_count
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.index_of(position).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.index_of(position).map(|i| &mut self.cells[i])
+    }
+
+    fn index_of(&self, position: Position) -> Option<usize> {
+        let (row, col) = position;
+        self.contains(position).then(|| row * self.col_count + col)
+    }
+
+    //every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        (0..self.row_count).cartesian_product(0..self.col_count)
+    }
+
+    //every cell along with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.col_count..(row + 1) * self.col_count]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        (0..self.row_count).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.rows().map(move |row| &row[col])
+    }
+
+    pub fn columns(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &T>> + ExactSizeIterator
+    {
+        (0..self.col_count).map(|col| self.column(col))
+    }
+
+    //the position offset from the given one, if it is still inside the grid
+    pub fn step(&self, (row, col): Position, (row_offset, col_offset): Offset) -> Option<Position> {
+        let position = (
+            row.checked_add_signed(row_offset)?,
+            col.checked_add_signed(col_offset)?,
+        );
+        self.contains(position).then_some(position)
+    }
+
+    //the adjacent position in the given direction, if it is inside the grid
+    pub fn neighbor(&self, position: Position, direction: Direction) -> Option<Position> {
+        self.step(position, direction.offset())
+    }
+
+    //the up to 4 positions sharing an edge with the given one
+    pub fn neighbors4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        ORTHOGONAL
+            .into_iter()
+            .filter_map(move |offset| self.step(position, offset))
+    }
+
+    //the up to 8 positions sharing an edge or a corner with the given one
+    pub fn neighbors8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        SURROUNDING
+            .into_iter()
+            .filter_map(move |offset| self.step(position, offset))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            row_count: self.row_count,
+            col_count: self.col_count,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(row_count: usize, col_count: usize, value: T) -> Self {
+        Grid {
+            cells: vec![value; row_count * col_count],
+            row_count,
+            col_count,
+        }
+    }
+
+    //rows become columns and columns become rows
+    pub fn transpose(&self) -> Self {
+        self.rebuild(self.col_count, self.row_count, |(row, col)| (col, row))
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        self.rebuild(self.col_count, self.row_count, |(row, col)| {
+            (self.row_count - 1 - col, row)
+        })
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self {
+        self.rebuild(self.col_count, self.row_count, |(row, col)| {
+            (col, self.col_count - 1 - row)
+        })
+    }
+
+    //builds a new grid where each position takes the cell at source(position) in this grid
+    fn rebuild(
+        &self,
+        row_count: usize,
+        col_count: usize,
+        source: impl Fn(Position) -> Position,
+    ) -> Self {
+        let cells = (0..row_count)
+            .cartesian_product(0..col_count)
+            .map(|position| self[source(position)].clone())
+            .collect();
+        Grid {
+            cells,
+            row_count,
+            col_count,
+        }
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        let index = self.index_of(position).unwrap_or_else(|| {
+            panic!(
+                "Position {:?} is outside of the {}x{} grid",
+                position, self.row_count, self.col_count
+            )
+        });
+        &self.cells[index]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        let index = self.index_of(position).unwrap_or_else(|| {
+            panic!(
+                "Position {:?} is outside of the {}x{} grid",
+                position, self.row_count, self.col_count
+            )
+        });
+        &mut self.cells[index]
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Location;
+
+    //2 rows and 3 columns
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456", |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parse_non_square() {
+        let grid = digits();
+        assert_eq!((grid.row_count(), grid.col_count()), (2, 3));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid.row(0), [1, 2, 3]);
+        assert_eq!(grid.column(2).copied().collect_vec(), [3, 6]);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.to_string(), "123\n456\n");
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = Grid::parse("123\n4x6", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(error.location, Location::new(2, 2));
+        assert_eq!(error.kind, ErrorKind::UnexpectedCharacter('x'));
+
+        let error = Grid::parse("123\n45\n789", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(error.location, Location::new(2, 3));
+        let expected = ErrorKind::RaggedRow {
+            expected: 3,
+            found: 2,
+        };
+        assert_eq!(error.kind, expected);
+
+        let error = Grid::parse("123\n4567", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(error.location, Location::new(2, 4));
+    }
+
+    #[test]
+    fn wrong_cell_count() {
+        let expected = ErrorKind::WrongCellCount {
+            expected: 6,
+            found: 5,
+        };
+        assert_eq!(Grid::new(2, 3, vec![0; 5]), Err(expected));
+    }
+
+    #[test]
+    fn transpose() {
+        let transposed = digits().transpose();
+        assert_eq!(transposed.to_string(), "14\n25\n36\n");
+        assert_eq!(transposed.transpose(), digits());
+    }
+
+    #[test]
+    fn rotate() {
+        let clockwise = digits().rotate_clockwise();
+        assert_eq!(clockwise.to_string(), "41\n52\n63\n");
+        let counterclockwise = digits().rotate_counterclockwise();
+        assert_eq!(counterclockwise.to_string(), "36\n25\n14\n");
+        assert_eq!(clockwise.rotate_counterclockwise(), digits());
+        let full_turn = (0..4).fold(digits(), |grid, _| grid.rotate_clockwise());
+        assert_eq!(full_turn, digits());
+    }
+
+    #[test]
+    fn neighbors_at_corner() {
+        let grid = digits();
+        assert_eq!(grid.neighbors4((0, 0)).collect_vec(), [(0, 1), (1, 0)]);
+        assert_eq!(
+            grid.neighbors8((0, 0)).collect_vec(),
+            [(0, 1), (1, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbor((0, 0), Direction::North), None);
+        assert_eq!(grid.neighbor((0, 0), Direction::West), None);
+        assert_eq!(grid.neighbor((1, 2), Direction::East), None);
+        assert_eq!(grid.neighbor((1, 2), Direction::South), None);
+        assert_eq!(grid.neighbor((1, 2), Direction::North), Some((0, 2)));
+    }
+
+    #[test]
+    fn neighbors_on_edge() {
+        let grid = digits();
+        assert_eq!(
+            grid.neighbors4((0, 1)).collect_vec(),
+            [(0, 2), (1, 1), (0, 0)]
+        );
+        assert_eq!(
+            grid.neighbors8((1, 1)).collect_vec(),
+            [(0, 0), (0, 1), (0, 2), (1, 2), (1, 0)]
+        );
+        assert_eq!(grid.step((1, 1), (-2, 0)), None);
+        assert_eq!(grid.step((1, 1), (-1, 1)), Some((0, 2)));
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::{Context, Result};
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+//environment variable that overrides the directory searched for dayN.txt
+pub const INPUTS_DIR_VAR: &str = "AOC_INPUTS_DIR";
+pub const DEFAULT_INPUTS_DIR: &str = "inputs";
+
+//Loads the puzzle input for the given day.
+//An explicit path is used if given, with "-" meaning stdin.
+//Otherwise, the input is read from <inputs dir>/day<N>.txt
+pub fn load(day: u8, path: Option<&Path>) -> Result<String> {
+    match path {
+        Some(path) => read(path),
+        None => read(default_path(day)),
+    }
+}
+
+pub fn inputs_dir() -> PathBuf {
+    env::var_os(INPUTS_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_INPUTS_DIR))
+}
+
+pub fn default_path(day: u8) -> PathBuf {
+    inputs_dir().join(format!("day{}.txt", day))
+}
+
+//reads from the file at path, or from stdin if path is "-"
+pub fn read(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .context("Error reading input from stdin")?;
+        return Ok(input);
+    }
+
+    fs::read_to_string(path)
+        .with_context(|| format!("Could not read input file '{}'", path.display()))
+}
+
+//Like load, but opens the input to be read a line at a time instead of reading all of it
+pub fn open(day: u8, path: Option<&Path>) -> Result<Box<dyn BufRead>> {
+    let path = path
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| default_path(day));
+    if path == Path::new("-") {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+
+    let file = File::open(&path)
+        .with_context(|| format!("Could not open input file '{}'", path.display()))?;
+    Ok(Box::new(BufReader::new(file)))
+}
@@ -0,0 +1,591 @@
+use anyhow::{bail, Context, Result};
+use aoc2023::{
+    answers::{self, Answers, Status},
+    generate, input,
+    registry::{self, Day, Part, Run},
+    render, stream,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use itertools::Itertools;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::{
+    cmp::Reverse,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a day's solution, or every day with "all"
+    Run {
+        /// Day number (1-25) or "all"
+        day: Selection,
+
+        /// Only run this part
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        /// Input file, or "-" for stdin. Defaults to <inputs dir>/day<N>.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+
+        /// Run all days at the same time and print a table of how long each part took
+        #[arg(long)]
+        parallel: bool,
+
+        /// Draw the input and what the part finds in it instead of printing the answer. Defaults to part 1
+        #[arg(long, value_enum, conflicts_with_all = ["format", "parallel"])]
+        render: Option<RenderFormat>,
+
+        /// Size in pixels of each cell of a ppm or svg drawing
+        #[arg(long, default_value_t = 4, requires = "render")]
+        scale: usize,
+
+        /// Read the input a line at a time instead of all at once, for days that support it
+        #[arg(long, conflicts_with_all = ["parallel", "render"])]
+        stream: bool,
+    },
+    /// Record whether an answer is correct or wrong in the answers file
+    Mark {
+        /// Day number
+        #[arg(value_parser = parse_day)]
+        day: u8,
+
+        /// Part number
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        status: Mark,
+
+        /// The answer to mark. Defaults to the answer the solution gives
+        #[arg(short, long)]
+        answer: Option<String>,
+
+        /// Input file used when the answer is not given, or "-" for stdin
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Rerun every day and compare the answers with the answers file
+    Verify,
+    /// Print a random input for a day
+    Gen {
+        /// Day number
+        #[arg(value_parser = parse_day)]
+        day: u8,
+
+        /// How large the input is, e.g. the number of lines. Defaults to about the size of a real input
+        #[arg(short, long)]
+        size: Option<usize>,
+
+        /// Seed for the random number generator. Defaults to a random seed
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// List the available days and their default input files
+    List,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Mark {
+    Correct,
+    Wrong,
+    /// Forget anything recorded about the answer
+    Unknown,
+}
+
+impl From<Mark> for Status {
+    fn from(mark: Mark) -> Self {
+        match mark {
+            Mark::Correct => Status::Correct,
+            Mark::Wrong => Status::Wrong,
+            Mark::Unknown => Status::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// "Part N = answer" lines
+    Text,
+    /// One JSON object per part, with timings
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum RenderFormat {
+    /// Colored text for terminals
+    Ansi,
+    /// Binary PPM image
+    Ppm,
+    /// SVG image
+    Svg,
+}
+
+impl From<RenderFormat> for render::Format {
+    fn from(format: RenderFormat) -> Self {
+        match format {
+            RenderFormat::Ansi => render::Format::Ansi,
+            RenderFormat::Ppm => render::Format::Ppm,
+            RenderFormat::Svg => render::Format::Svg,
+        }
+    }
+}
+
+//a single line of --format json output
+#[derive(Serialize)]
+struct Record {
+    day: u8,
+    part: u8,
+    answer: Option<String>,
+    status: Option<Status>,
+    parse_time_ns: Option<u128>,
+    solve_time_ns: Option<u128>,
+    error: Option<String>,
+}
+
+impl Record {
+    fn new(day: &Day, part: Part, run: &Run, answers: &Answers) -> Self {
+        let (answer, error) = match &run.answer {
+            Ok(answer) => (Some(answer.clone()), None),
+            Err(e) => (None, Some(format!("{:#}", e))),
+        };
+        Record {
+            day: day.number,
+            part: part.number(),
+            status: answer
+                .as_ref()
+                .map(|answer| answers.status(day.number, part, answer)),
+            answer,
+            parse_time_ns: Some(run.parse_time.as_nanos()),
+            solve_time_ns: run.solve_time.map(|time| time.as_nanos()),
+            error,
+        }
+    }
+
+    //for when the input could not even be loaded
+    fn failed(day: &Day, part: Part, error: &anyhow::Error) -> Self {
+        Record {
+            day: day.number,
+            part: part.number(),
+            answer: None,
+            status: None,
+            parse_time_ns: None,
+            solve_time_ns: None,
+            error: Some(format!("{:#}", error)),
+        }
+    }
+
+    fn print(&self) -> Result<()> {
+        println!("{}", serde_json::to_string(self)?);
+        Ok(())
+    }
+
+    fn total_time(&self) -> Duration {
+        let nanos = self.parse_time_ns.unwrap_or(0) + self.solve_time_ns.unwrap_or(0);
+        Duration::from_nanos(nanos as u64)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Selection {
+    All,
+    Day(u8),
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(Selection::All);
+        }
+        parse_day(s)
+            .map(Selection::Day)
+            .map_err(|_| format!("expected a day number or \"all\", found '{}'", s))
+    }
+}
+
+fn parse_day(s: &str) -> Result<u8, String> {
+    let number = s
+        .parse()
+        .map_err(|_| format!("expected a day number, found '{}'", s))?;
+    if registry::get(number).is_none() {
+        return Err(format!("day {} is not implemented", number));
+    }
+    Ok(number)
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            format,
+            parallel,
+            render,
+            scale,
+            stream,
+        } => {
+            if let Some(render) = render {
+                let Selection::Day(number) = day else {
+                    bail!("--render can only be used with a single day");
+                };
+                let renderer = render::get(number).context("This day cannot be rendered")?;
+                let part = if part == Some(2) {
+                    Part::Two
+                } else {
+                    Part::One
+                };
+                let input = input::load(number, input.as_deref())?;
+                let image = renderer.render(&input, part).with_context(|| {
+                    format!("Error rendering day {} part {}", number, part.number())
+                })?;
+                io::stdout().write_all(&image.encode(render.into(), scale))?;
+                return Ok(());
+            }
+
+            let parts = match part {
+                Some(1) => vec![Part::One],
+                Some(_) => vec![Part::Two],
+                None => Part::iter().collect(),
+            };
+            let answers = Answers::load(answers::default_path())?;
+            match day {
+                Selection::All if stream => {
+                    bail!("--stream can only be used with a single day")
+                }
+                Selection::Day(number) if stream => {
+                    run_streaming(number, &parts, input, format, &answers)
+                }
+                Selection::Day(_) if parallel => {
+                    bail!("--parallel can only be used when running all days")
+                }
+                Selection::Day(number) => {
+                    let day = registry::get(number).context("Unknown day")?;
+                    //a part that was asked for is run even if the day does not have it, to say so
+                    let parts = match part {
+                        Some(_) => parts,
+                        None => day.parts().collect(),
+                    };
+                    run(day, &parts, input, format, &answers)
+                }
+                Selection::All if input.is_some() => {
+                    bail!("--input cannot be used when running all days")
+                }
+                Selection::All if parallel => run_parallel(&parts, format, &answers),
+                Selection::All => run_all(&parts, format, &answers),
+            }
+        }
+        Command::Mark {
+            day,
+            part,
+            status,
+            answer,
+            input,
+        } => {
+            let day = registry::get(day).context("Unknown day")?;
+            let part = if part == 1 { Part::One } else { Part::Two };
+            let answer = match answer {
+                Some(answer) => answer,
+                None => {
+                    let input = input::load(day.number, input.as_deref())?;
+                    day.solve(part, &input).with_context(|| {
+                        format!("Error solving day {} part {}", day.number, part.number())
+                    })?
+                }
+            };
+
+            let path = answers::default_path();
+            let mut answers = Answers::load(&path)?;
+            let status = status.into();
+            answers.mark(day.number, part, &answer, status);
+            answers.save(&path)?;
+            println!(
+                "Day {} part {}: marked {} as {}",
+                day.number,
+                part.number(),
+                answer,
+                status
+            );
+            Ok(())
+        }
+        Command::Verify => verify(&Answers::load(answers::default_path())?),
+        Command::Gen { day, size, seed } => {
+            let generator = generate::get(day).context("No generator for this day")?;
+            let size = size.unwrap_or(generator.default_size);
+            if let Some(max_size) = generator.max_size.filter(|&max_size| size > max_size) {
+                bail!(
+                    "Day {} can only generate inputs up to --size {}",
+                    day,
+                    max_size
+                );
+            }
+            let seed = seed.unwrap_or_else(rand::random);
+            eprintln!(
+                "Generating day {} with --size {} --seed {}",
+                day, size, seed
+            );
+            println!("{}", generator.generate(size, seed));
+            Ok(())
+        }
+        Command::List => {
+            for day in registry::DAYS {
+                println!(
+                    "day{:<3} {}",
+                    day.number,
+                    input::default_path(day.number).display()
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+fn run(
+    day: &Day,
+    parts: &[Part],
+    input: Option<PathBuf>,
+    format: Format,
+    answers: &Answers,
+) -> Result<()> {
+    let input = match input::load(day.number, input.as_deref()) {
+        Ok(input) => input,
+        Err(e) if format == Format::Json => {
+            for &part in parts {
+                Record::failed(day, part, &e).print()?;
+            }
+            return Err(e);
+        }
+        Err(e) => return Err(e),
+    };
+
+    let mut failed = false;
+    for &part in parts {
+        let run = day.run(part, &input);
+        match format {
+            Format::Text => {
+                let answer = run.answer.with_context(|| {
+                    format!("Error solving day {} part {}", day.number, part.number())
+                })?;
+                match answers.status(day.number, part, &answer) {
+                    Status::Unknown => println!("Part {} = {}", part.number(), answer),
+                    status => println!("Part {} = {} ({})", part.number(), answer, status),
+                }
+            }
+            Format::Json => {
+                Record::new(day, part, &run, answers).print()?;
+                failed |= run.answer.is_err();
+            }
+        }
+    }
+    if failed {
+        bail!("Error solving day {}", day.number);
+    }
+    Ok(())
+}
+
+//solves each part while reading the input, so the input never has to fit in memory
+fn run_streaming(
+    number: u8,
+    parts: &[Part],
+    input: Option<PathBuf>,
+    format: Format,
+    answers: &Answers,
+) -> Result<()> {
+    let streamer = stream::get(number).context("This day cannot be streamed")?;
+    if format == Format::Json {
+        bail!("--stream only supports text output");
+    }
+    //stdin can only be read once
+    if parts.len() > 1 && input.as_deref() == Some(Path::new("-")) {
+        bail!("--stream can only solve one part from stdin, choose one with --part");
+    }
+
+    for &part in parts {
+        let mut reader = input::open(number, input.as_deref())?;
+        let answer = streamer
+            .solve(part, &mut reader)
+            .with_context(|| format!("Error solving day {} part {}", number, part.number()))?;
+        match answers.status(number, part, &answer) {
+            Status::Unknown => println!("Part {} = {}", part.number(), answer),
+            status => println!("Part {} = {} ({})", part.number(), answer, status),
+        }
+    }
+    Ok(())
+}
+
+fn run_all(parts: &[Part], format: Format, answers: &Answers) -> Result<()> {
+    let mut failures = 0;
+    for day in registry::DAYS {
+        if format == Format::Text {
+            println!("Day {}", day.number);
+        }
+        let parts = parts
+            .iter()
+            .copied()
+            .filter(|&part| day.has_part(part))
+            .collect_vec();
+        if let Err(error) = run(day, &parts, None, format, answers) {
+            eprintln!("Error: {:#}", error);
+            failures += 1;
+        }
+    }
+    if failures > 0 {
+        bail!("{} day(s) failed", failures);
+    }
+    Ok(())
+}
+
+//runs every part of every day at once.
+//the times are measured while the other days are running too, so they are only comparable to each other.
+fn run_parallel(parts: &[Part], format: Format, answers: &Answers) -> Result<()> {
+    let start = Instant::now();
+    let records = registry::DAYS
+        .par_iter()
+        .flat_map(|day| match input::load(day.number, None) {
+            Ok(input) => parts
+                .par_iter()
+                .filter(|&&part| day.has_part(part))
+                .map(|&part| Record::new(day, part, &day.run(part, &input), answers))
+                .collect::<Vec<_>>(),
+            Err(e) => parts
+                .iter()
+                .filter(|&&part| day.has_part(part))
+                .map(|&part| Record::failed(day, part, &e))
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    let wall_time = start.elapsed();
+
+    match format {
+        Format::Text => print_timings(&records, wall_time),
+        Format::Json => {
+            for record in &records {
+                record.print()?;
+            }
+        }
+    }
+
+    let failures = records
+        .iter()
+        .filter(|record| record.error.is_some())
+        .count();
+    if failures > 0 {
+        bail!("{} part(s) failed", failures);
+    }
+    Ok(())
+}
+
+//a table of every part, slowest first
+fn print_timings(records: &[Record], wall_time: Duration) {
+    let records = records
+        .iter()
+        .sorted_by_key(|record| Reverse(record.total_time()))
+        .collect_vec();
+    let total_time = records
+        .iter()
+        .map(|record| record.total_time())
+        .sum::<Duration>();
+    let share =
+        |time: Duration| 100.0 * time.as_secs_f64() / total_time.as_secs_f64().max(f64::EPSILON);
+    let time = |nanos: Option<u128>| match nanos {
+        Some(nanos) => format!("{:.1?}", Duration::from_nanos(nanos as u64)),
+        None => "-".to_string(),
+    };
+
+    println!(
+        "{:>3} {:>4} {:>10} {:>10} {:>10} {:>6}  Answer",
+        "Day", "Part", "Parse", "Solve", "Total", "Share"
+    );
+    for record in &records {
+        let answer = match (&record.answer, record.status, &record.error) {
+            (_, _, Some(error)) => format!("error: {}", error),
+            (Some(answer), Some(Status::Unknown) | None, _) => answer.clone(),
+            (Some(answer), Some(status), _) => format!("{} ({})", answer, status),
+            (None, _, None) => String::new(),
+        };
+        println!(
+            "{:>3} {:>4} {:>10} {:>10} {:>10} {:>5.1}%  {}",
+            record.day,
+            record.part,
+            time(record.parse_time_ns),
+            time(record.solve_time_ns),
+            format!("{:.1?}", record.total_time()),
+            share(record.total_time()),
+            answer
+        );
+    }
+
+    println!();
+    if let Some(slowest) = records.first() {
+        println!(
+            "Slowest: day {} part {} ({:.1}% of the total)",
+            slowest.day,
+            slowest.part,
+            share(slowest.total_time())
+        );
+    }
+    println!(
+        "Total: {:.1?} across {} parts, {:.1?} wall clock",
+        total_time,
+        records.len(),
+        wall_time
+    );
+}
+
+//reruns every day with an input and reports how each answer compares to the recorded one
+fn verify(answers: &Answers) -> Result<()> {
+    let mut mismatches = 0;
+    for day in registry::DAYS {
+        let input = match input::load(day.number, None) {
+            Ok(input) => input,
+            //only a problem if there is something to check
+            Err(e) if answers.has_day(day.number) => {
+                println!("Day {}: {:#}", day.number, e);
+                mismatches += 1;
+                continue;
+            }
+            Err(_) => continue,
+        };
+
+        for part in day.parts() {
+            let result = match day.solve(part, &input) {
+                Ok(answer) => match answers.status(day.number, part, &answer) {
+                    Status::Wrong => {
+                        mismatches += 1;
+                        match answers.correct(day.number, part) {
+                            Some(correct) => format!("{} (wrong, expected {})", answer, correct),
+                            None => format!("{} (wrong)", answer),
+                        }
+                    }
+                    status => format!("{} ({})", answer, status),
+                },
+                Err(e) => {
+                    mismatches += 1;
+                    format!("error: {:#}", e)
+                }
+            };
+            println!("Day {} part {}: {}", day.number, part.number(), result);
+        }
+    }
+    if mismatches > 0 {
+        bail!("{} answer(s) did not match the answers file", mismatches);
+    }
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use crate::*;
+use anyhow::Result;
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+//the outcome of solving one part, along with how long each step took
+pub struct Run {
+    pub answer: Result<String>,
+    pub parse_time: Duration,
+    //None if parsing failed
+    pub solve_time: Option<Duration>,
+}
+
+pub type Solver = fn(&str) -> Run;
+
+pub struct Day {
+    pub number: u8,
+    pub part1: Solver,
+    pub part2: Solver,
+    pub has_part2: bool,
+}
+
+impl Day {
+    pub fn has_part(&self, part: Part) -> bool {
+        part == Part::One || self.has_part2
+    }
+
+    //the parts with something to solve
+    pub fn parts(&self) -> impl Iterator<Item = Part> + '_ {
+        Part::iter().filter(|&part| self.has_part(part))
+    }
+
+    pub fn solver(&self, part: Part) -> Solver {
+        match part {
+            Part::One => self.part1,
+            Part::Two => self.part2,
+        }
+    }
+
+    pub fn run(&self, part: Part, input: &str) -> Run {
+        self.solver(part)(input)
+    }
+
+    pub fn solve(&self, part: Part, input: &str) -> Result<String> {
+        self.run(part, input).answer
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [Self::One, Self::Two].into_iter()
+    }
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+}
+
+fn timed<S: Solution, A: Display>(
+    input: &str,
+    solve: impl FnOnce(&S::Input<'_>) -> Result<A>,
+) -> Run {
+    let start = Instant::now();
+    let parsed = S::parse(input);
+    let parse_time = start.elapsed();
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return Run {
+                answer: Err(e.into()),
+                parse_time,
+                solve_time: None,
+            }
+        }
+    };
+
+    let start = Instant::now();
+    let answer = solve(&parsed).map(|answer| answer.to_string());
+    Run {
+        answer,
+        parse_time,
+        solve_time: Some(start.elapsed()),
+    }
+}
+
+fn solve_part1<S: Solution>(input: &str) -> Run {
+    timed::<S, _>(input, S::part1)
+}
+
+fn solve_part2<S: Solution>(input: &str) -> Run {
+    timed::<S, _>(input, S::part2)
+}
+
+macro_rules! days {
+    ($($solution:ty),* $(,)?) => {
+        pub const DAYS: &[Day] = &[$(
+            Day {
+                number: <$solution>::DAY,
+                part1: solve_part1::<$solution>,
+                part2: solve_part2::<$solution>,
+                has_part2: <$solution>::HAS_PART2,
+            },
+        )*];
+    };
+}
+
+days! {
+    day1::Day1,
+    day2::Day2,
+    day3::Day3,
+    day4::Day4,
+    day5::Day5,
+    day6::Day6,
+    day7::Day7,
+    day8::Day8,
+    day9::Day9,
+    day10::Day10,
+    day11::Day11,
+    day12::Day12,
+    day13::Day13,
+    day14::Day14,
+    day15::Day15,
+    day16::Day16,
+    day17::Day17,
+    day18::Day18,
+    day19::Day19,
+    day20::Day20,
+    day21::Day21,
+    day22::Day22,
+    day23::Day23,
+    day24::Day24,
+    day25::Day25,
+}
+
+pub fn get(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
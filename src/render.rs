@@ -0,0 +1,238 @@
+use crate::{
+    grid::{Grid, Position},
+    registry::Part,
+    *,
+};
+use anyhow::Result;
+use itertools::Itertools;
+use std::fmt::Write;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    pub const BLACK: Color = Color(0, 0, 0);
+    pub const DARK_GRAY: Color = Color(60, 60, 60);
+    pub const GRAY: Color = Color(140, 140, 140);
+    pub const WHITE: Color = Color(230, 230, 230);
+    pub const RED: Color = Color(220, 50, 47);
+    pub const GREEN: Color = Color(80, 200, 80);
+    pub const BLUE: Color = Color(60, 120, 220);
+    pub const YELLOW: Color = Color(240, 200, 40);
+
+    //somewhere between from (at 0) and to (at 1)
+    pub fn blend(from: Color, to: Color, amount: f64) -> Color {
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * amount.clamp(0.0, 1.0)) as u8;
+        Color(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+    }
+
+    fn hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+//one cell of an image, drawn as a colored character in a terminal and as a colored square otherwise
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Pixel {
+    pub symbol: char,
+    pub color: Color,
+}
+
+impl Pixel {
+    pub fn new(symbol: char, color: Color) -> Self {
+        Pixel { symbol, color }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Ansi,
+    Ppm,
+    Svg,
+}
+
+//A grid of pixels, usually a day's grid with some of its cells highlighted
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Image {
+    pub pixels: Grid<Pixel>,
+}
+
+impl Image {
+    pub fn new<T>(grid: &Grid<T>, pixel: impl FnMut(&T) -> Pixel) -> Self {
+        Image {
+            pixels: grid.map(pixel),
+        }
+    }
+
+    //recolors the given positions, keeping their symbols
+    pub fn overlay(&mut self, positions: impl IntoIterator<Item = Position>, color: Color) {
+        for position in positions {
+            if let Some(pixel) = self.pixels.get_mut(position) {
+                pixel.color = color;
+            }
+        }
+    }
+
+    pub fn encode(&self, format: Format, scale: usize) -> Vec<u8> {
+        match format {
+            Format::Ansi => self.to_ansi().into_bytes(),
+            Format::Ppm => self.to_ppm(scale),
+            Format::Svg => self.to_svg(scale).into_bytes(),
+        }
+    }
+
+    //24-bit colored text for terminals
+    pub fn to_ansi(&self) -> String {
+        let mut ansi = String::new();
+        for row in self.pixels.rows() {
+            for (color, pixels) in &row.iter().group_by(|pixel| pixel.color) {
+                let Color(r, g, b) = color;
+                let symbols = pixels.map(|pixel| pixel.symbol).collect::<String>();
+                let _ = write!(ansi, "\x1b[38;2;{};{};{}m{}", r, g, b, symbols);
+            }
+            ansi.push_str("\x1b[0m\n");
+        }
+        ansi
+    }
+
+    //binary PPM, with every pixel scaled up to a scale x scale square
+    pub fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        let scale = scale.max(1);
+        let width = self.pixels.col_count() * scale;
+        let height = self.pixels.row_count() * scale;
+        let mut ppm = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        for row in self.pixels.rows() {
+            let line = row
+                .iter()
+                .flat_map(|pixel| {
+                    let Color(r, g, b) = pixel.color;
+                    [r, g, b].repeat(scale)
+                })
+                .collect_vec();
+            for _ in 0..scale {
+                ppm.extend(&line);
+            }
+        }
+        ppm
+    }
+
+    //a rectangle for every run of pixels of the same color in a row
+    pub fn to_svg(&self, scale: usize) -> String {
+        let width = self.pixels.col_count();
+        let height = self.pixels.row_count();
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n",
+            width * scale.max(1),
+            height * scale.max(1),
+            width,
+            height
+        );
+        for (y, row) in self.pixels.rows().enumerate() {
+            let mut x = 0;
+            for (color, pixels) in &row.iter().group_by(|pixel| pixel.color) {
+                let run = pixels.count();
+                let _ = writeln!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\" fill=\"{}\"/>",
+                    x,
+                    y,
+                    run,
+                    color.hex()
+                );
+                x += run;
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+//A day that can draw its input, along with what the given part finds in it
+pub trait Render: Solution {
+    fn render(input: &Self::Input<'_>, part: Part) -> Result<Image>;
+}
+
+pub struct Renderer {
+    pub day: u8,
+    render: fn(&str, Part) -> Result<Image>,
+}
+
+impl Renderer {
+    pub fn render(&self, input: &str, part: Part) -> Result<Image> {
+        (self.render)(input, part)
+    }
+}
+
+fn parse_and_render<S: Render>(input: &str, part: Part) -> Result<Image> {
+    S::render(&S::parse(input)?, part)
+}
+
+macro_rules! renderers {
+    ($($solution:ty),* $(,)?) => {
+        pub const RENDERERS: &[Renderer] = &[$(
+            Renderer {
+                day: <$solution>::DAY,
+                render: parse_and_render::<$solution>,
+            },
+        )*];
+    };
+}
+
+renderers! {
+    day10::Day10,
+    day14::Day14,
+    day16::Day16,
+    day17::Day17,
+    day18::Day18,
+}
+
+pub fn get(day: u8) -> Option<&'static Renderer> {
+    RENDERERS.iter().find(|renderer| renderer.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image() -> Image {
+        let grid = Grid::parse("#.\n..", Some).unwrap();
+        let mut image = Image::new(&grid, |&c| Pixel::new(c, Color::GRAY));
+        image.overlay([(0, 0)], Color::RED);
+        image
+    }
+
+    #[test]
+    fn ppm() {
+        let ppm = image().to_ppm(1);
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend([220, 50, 47]);
+        expected.extend([140; 9]);
+        assert_eq!(ppm, expected);
+    }
+
+    #[test]
+    fn svg_merges_runs() {
+        let svg = image().to_svg(10);
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20\" height=\"20\"")
+        );
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.contains("<rect x=\"0\" y=\"1\" width=\"2\" height=\"1\" fill=\"#8c8c8c\"/>"));
+    }
+
+    #[test]
+    fn every_renderer_works_on_generated_input() -> Result<()> {
+        for renderer in RENDERERS {
+            let input = generate::get(renderer.day).unwrap().generate(8, 0);
+            for part in Part::iter() {
+                //day 18's part 2 trench is far too big to draw
+                if renderer.day == 18 && part == Part::Two {
+                    assert!(renderer.render(&input, part).is_err());
+                } else {
+                    renderer.render(&input, part)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use anyhow::Result;
+use std::fmt::Display;
+
+//A day's puzzle, split into parsing and the two parts so each can be used (or timed) on its own
+pub trait Solution {
+    const DAY: u8;
+    //false if the second star only needs the others, in which case part2 always fails
+    const HAS_PART2: bool = true;
+
+    type Input<'a>;
+    type Error: std::error::Error + Send + Sync + 'static;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, Self::Error>;
+    fn part1(input: &Self::Input<'_>) -> Result<Self::Answer1>;
+    fn part2(input: &Self::Input<'_>) -> Result<Self::Answer2>;
+
+    fn solve_part1(input: &str) -> Result<Self::Answer1> {
+        Self::part1(&Self::parse(input)?)
+    }
+
+    fn solve_part2(input: &str) -> Result<Self::Answer2> {
+        Self::part2(&Self::parse(input)?)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{
+    error::{self, Location},
+    registry::Part,
+    *,
+};
+use anyhow::{Context, Result};
+use std::{
+    fmt::{Debug, Display},
+    io::BufRead,
+};
+
+//A day that can solve each part one line at a time, so it never needs more than
+//a bounded amount of the input in memory
+pub trait Streaming: Solution {
+    fn stream_part1(input: impl BufRead) -> Result<Self::Answer1>;
+    fn stream_part2(input: impl BufRead) -> Result<Self::Answer2>;
+}
+
+//parses input line by line, with errors pointing at the line they were found on
+pub fn parse_lines<T, K>(
+    input: impl BufRead,
+    mut parse: impl FnMut(&str) -> Result<T, error::Error<K>>,
+) -> impl Iterator<Item = Result<T>>
+where
+    K: Display + Debug + Send + Sync + 'static,
+{
+    input.lines().enumerate().map(move |(i, line)| {
+        let line = line.context("Error reading input")?;
+        parse(&line).map_err(|e| e.offset_by(Location::new(i + 1, 1)).into())
+    })
+}
+
+pub type StreamSolver = fn(&mut dyn BufRead) -> Result<String>;
+
+pub struct Streamer {
+    pub day: u8,
+    part1: StreamSolver,
+    part2: StreamSolver,
+}
+
+impl Streamer {
+    pub fn solve(&self, part: Part, input: &mut dyn BufRead) -> Result<String> {
+        match part {
+            Part::One => (self.part1)(input),
+            Part::Two => (self.part2)(input),
+        }
+    }
+}
+
+fn stream_part1<S: Streaming>(input: &mut dyn BufRead) -> Result<String> {
+    S::stream_part1(input).map(|answer| answer.to_string())
+}
+
+fn stream_part2<S: Streaming>(input: &mut dyn BufRead) -> Result<String> {
+    S::stream_part2(input).map(|answer| answer.to_string())
+}
+
+macro_rules! streamers {
+    ($($solution:ty),* $(,)?) => {
+        pub const STREAMERS: &[Streamer] = &[$(
+            Streamer {
+                day: <$solution>::DAY,
+                part1: stream_part1::<$solution>,
+                part2: stream_part2::<$solution>,
+            },
+        )*];
+    };
+}
+
+streamers! {
+    day1::Day1,
+    day2::Day2,
+    day4::Day4,
+    day7::Day7,
+    day9::Day9,
+    day12::Day12,
+}
+
+pub fn get(day: u8) -> Option<&'static Streamer> {
+    STREAMERS.iter().find(|streamer| streamer.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //streaming has to give the same answers as solving the whole input at once
+    #[test]
+    fn same_answers_as_registry() -> Result<()> {
+        for streamer in STREAMERS {
+            let day = registry::get(streamer.day).unwrap();
+            for seed in 0..3 {
+                let input = generate::get(streamer.day).unwrap().generate(20, seed);
+                for part in Part::iter() {
+                    let streamed = streamer.solve(part, &mut input.as_bytes())?;
+                    assert_eq!(
+                        streamed,
+                        day.solve(part, &input)?,
+                        "day {} part {}",
+                        streamer.day,
+                        part.number()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        let input = "Game 1: 3 blue\nGame 2: 3 purple\n";
+        let error = day2::Day2::stream_part1(input.as_bytes()).unwrap_err();
+        let error = error.downcast::<day2::Error>().unwrap();
+        assert_eq!(error.location, Location::new(2, 11));
+    }
+}
@@ -0,0 +1,57 @@
+//Checks every day against the real answers recorded in answers.toml, if it exists.
+//See aoc2023::answers for the file format.
+//Inputs are loaded from the inputs directory, the same way the aoc binary does.
+use aoc2023::{
+    answers::{Answers, Status},
+    input,
+    registry::{self, Day},
+};
+use std::path::Path;
+
+const ANSWERS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.toml");
+
+#[test]
+fn real_answers() {
+    if !Path::new(ANSWERS_PATH).exists() {
+        println!("No answers.toml found, skipping real input checks");
+        return;
+    }
+    let answers = Answers::load(ANSWERS_PATH).expect("answers.toml should be valid");
+
+    let failures = registry::DAYS
+        .iter()
+        .filter(|day| answers.has_day(day.number))
+        .flat_map(|day| check_day(day, &answers))
+        .collect::<Vec<_>>();
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+//returns a message for every part that is known to be wrong
+fn check_day(day: &Day, answers: &Answers) -> Vec<String> {
+    let input = match input::load(day.number, None) {
+        Ok(input) => input,
+        Err(e) => return vec![format!("Day {}: {:#}", day.number, e)],
+    };
+
+    day.parts()
+        .filter_map(|part| {
+            let message = match day.solve(part, &input) {
+                Ok(answer) => match answers.status(day.number, part, &answer) {
+                    Status::Wrong => match answers.correct(day.number, part) {
+                        Some(correct) => format!("expected {}, got {}", correct, answer),
+                        None => format!("{} is known to be wrong", answer),
+                    },
+                    _ => return None,
+                },
+                Err(e) => format!("error: {:#}", e),
+            };
+            Some(format!(
+                "Day {} part {}: {}",
+                day.number,
+                part.number(),
+                message
+            ))
+        })
+        .collect()
+}